@@ -0,0 +1,118 @@
+use polygon::cmd::{generate, Family, Nesting, Parameters};
+use polygon::tree::Node;
+use polygon::IndexedNodes;
+
+#[cfg(feature = "naive")] use polygon::cmd::process_naive;
+#[cfg(feature = "frames")] use polygon::cmd::process_frames;
+#[cfg(feature = "quadtree")] use polygon::cmd::process_quadtree;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+use std::hint::black_box;
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+use std::time::{Duration, Instant};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const SIZES: [usize; 3] = [100, 300, 1000];
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+const MEASUREMENT_TIME: Duration = Duration::from_secs(2);
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+const WARM_UP_TIME: Duration = Duration::from_millis(500);
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+fn input(polygon_count: usize) -> IndexedNodes
+{
+    let parameters = Parameters {
+        attempts: 1000,
+        corner_count: 8,
+        dimension: 40. * (polygon_count as f64).sqrt(),
+        family: Family::Star,
+        nesting: Nesting { branching: 2, depth: 1, fill: 0.5 },
+        polygon_count,
+        radius: 10.
+    };
+
+    generate(&parameters, &mut ChaCha8Rng::seed_from_u64(0))
+        .unwrap()
+        .polygons
+        .into_iter()
+        .enumerate()
+        .map(|(i, polygon)| Node::new((i as isize, polygon)))
+        .collect()
+}
+
+// Runs `f` repeatedly for `MEASUREMENT_TIME` after warming up, and prints the
+// mean time of an iteration along with the fastest and slowest ones.
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+fn bench<F, T>(name: &str, f: F) where F: Fn() -> T
+{
+    let start = Instant::now();
+
+    while start.elapsed() < WARM_UP_TIME {
+        black_box(f());
+    }
+
+    let mut times = Vec::new();
+    let start = Instant::now();
+
+    while start.elapsed() < MEASUREMENT_TIME {
+        let iteration = Instant::now();
+
+        black_box(f());
+        times.push(iteration.elapsed());
+    }
+
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+
+    println!(
+        "{:<24} time: [{:?} {:?} {:?}] ({} iterations)",
+        name,
+        times.iter().min().unwrap(),
+        mean,
+        times.iter().max().unwrap(),
+        times.len()
+    );
+}
+
+fn main()
+{
+    for size in SIZES {
+        #[cfg_attr(
+            not(
+                any(feature = "frames", feature = "naive", feature = "quadtree")
+            ),
+            allow(unused_variables)
+        )]
+        let nodes = input(size);
+
+        #[cfg(feature = "frames")]
+        bench(
+            &format!("process_frames/{}", size),
+            || process_frames(black_box(&nodes))
+        );
+
+        #[cfg(feature = "naive")]
+        bench(
+            &format!("process_naive/{}", size),
+            || process_naive(black_box(&nodes))
+        );
+
+        #[cfg(feature = "quadtree")]
+        bench(
+            &format!("process_quadtree/{}", size),
+            || process_quadtree(black_box(&nodes))
+        );
+    }
+}
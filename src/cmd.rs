@@ -1,8 +1,34 @@
+pub mod algorithm;
+pub use algorithm::{HierarchyAlgorithm, Registry};
+
+pub mod bench;
+
+pub mod compare;
+pub use compare::{compare, Disagreement};
+
+pub mod duplicate;
+pub use duplicate::{duplicates, process_with_policy};
+pub use duplicate::{DuplicateError, DuplicatePolicy};
+
 pub mod generate;
-pub use generate::generate;
+pub use generate::{adversarial, generate, Case, Family, Generation};
+pub use generate::{Nesting, Parameters};
+
+pub mod locate;
+pub use locate::locate;
+
+pub mod overlap;
+pub use overlap::{containment_dag, overlaps};
 
 pub mod process;
 
-#[cfg(feature = "dac")] pub use process::process_dac;
-#[cfg(feature = "frames")] pub use process::process_frames;
-#[cfg(feature = "naive")] pub use process::process_naive;
+pub use process::ProcessStats;
+
+#[cfg(feature = "frames")]
+pub use process::{process_frames, process_frames_with_stats};
+
+#[cfg(feature = "naive")]
+pub use process::{process_naive, process_naive_with_stats};
+
+#[cfg(feature = "quadtree")]
+pub use process::{process_quadtree, process_quadtree_with_stats};
@@ -0,0 +1,319 @@
+use super::process::ProcessStats;
+
+use super::super::{IndexedArena, IndexedNodes};
+
+#[cfg(feature = "frames")] use super::process::process_frames_with_stats;
+#[cfg(feature = "naive")] use super::process::process_naive_with_stats;
+
+#[cfg(feature = "quadtree")]
+use super::process::process_quadtree_with_stats;
+
+/**********/
+/* TRAITS */
+/**********/
+
+pub trait HierarchyAlgorithm
+{
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn build(&self, nodes: &IndexedNodes) -> IndexedArena;
+
+    // Algorithms without counters report empty statistics.
+
+    fn build_with_stats(&self, nodes: &IndexedNodes)
+        -> (IndexedArena, ProcessStats)
+    {
+        (self.build(nodes), ProcessStats::default())
+    }
+}
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[cfg(feature = "frames")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Frames;
+
+#[cfg(feature = "naive")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Naive;
+
+#[cfg(feature = "quadtree")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Quadtree;
+
+// The algorithms known by name. `Registry::default()` holds the ones built
+// into this crate, and others can be added with `register`.
+
+#[derive(Default)]
+pub struct Registry { algorithms: Vec<Box<dyn HierarchyAlgorithm>> }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+#[cfg(feature = "frames")]
+impl HierarchyAlgorithm for Frames {
+    fn name(&self) -> &str { "frames" }
+
+    fn description(&self) -> &str
+    {
+        "Tests the frames of the polygons before the polygons themselves"
+    }
+
+    fn build(&self, nodes: &IndexedNodes) -> IndexedArena
+    {
+        self.build_with_stats(nodes).0
+    }
+
+    fn build_with_stats(&self, nodes: &IndexedNodes)
+        -> (IndexedArena, ProcessStats)
+    {
+        process_frames_with_stats(nodes)
+    }
+}
+
+#[cfg(feature = "naive")]
+impl HierarchyAlgorithm for Naive {
+    fn name(&self) -> &str { "naive" }
+
+    fn description(&self) -> &str
+    {
+        "Tests every pair of sibling polygons directly"
+    }
+
+    fn build(&self, nodes: &IndexedNodes) -> IndexedArena
+    {
+        self.build_with_stats(nodes).0
+    }
+
+    fn build_with_stats(&self, nodes: &IndexedNodes)
+        -> (IndexedArena, ProcessStats)
+    {
+        process_naive_with_stats(nodes)
+    }
+}
+
+#[cfg(feature = "quadtree")]
+impl HierarchyAlgorithm for Quadtree {
+    fn name(&self) -> &str { "quadtree" }
+
+    fn description(&self) -> &str
+    {
+        "Tests the polygons whose frames meet in a quadtree"
+    }
+
+    fn build(&self, nodes: &IndexedNodes) -> IndexedArena
+    {
+        self.build_with_stats(nodes).0
+    }
+
+    fn build_with_stats(&self, nodes: &IndexedNodes)
+        -> (IndexedArena, ProcessStats)
+    {
+        process_quadtree_with_stats(nodes)
+    }
+}
+
+impl Registry {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn builtin() -> Self
+    {
+        #[allow(unused_mut)]
+        let mut ret = Self::default();
+
+        #[cfg(feature = "frames")] ret.register(Frames);
+        #[cfg(feature = "naive")] ret.register(Naive);
+        #[cfg(feature = "quadtree")] ret.register(Quadtree);
+
+        ret
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    // An algorithm replaces any registered one with the same name.
+
+    pub fn register<A>(&mut self, algorithm: A)
+        where A: HierarchyAlgorithm + 'static
+    {
+        let algorithm = Box::new(algorithm);
+
+        match self.position(algorithm.name()) {
+            Some(index) => self.algorithms[index] = algorithm,
+            None => self.algorithms.push(algorithm)
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn get(&self, name: &str) -> Option<&dyn HierarchyAlgorithm>
+    {
+        self.position(name).map(|index| self.algorithms[index].as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool { self.algorithms.is_empty() }
+    pub fn len(&self) -> usize { self.algorithms.len() }
+
+    fn position(&self, name: &str) -> Option<usize>
+    {
+        self.algorithms.iter().position(|algorithm| algorithm.name() == name)
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn HierarchyAlgorithm>
+    {
+        self.algorithms.iter().map(Box::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::super::geo::polygon::{Any, Polygon, Rectangle};
+    use super::super::super::geo::polygon::PREPARED_MIN_LEN;
+    use super::super::super::geo::Point;
+    use super::super::super::tree::{Arena, Node};
+
+    use std::f64::consts::TAU;
+
+    struct Flat;
+
+    impl HierarchyAlgorithm for Flat {
+        fn name(&self) -> &str { "flat" }
+        fn description(&self) -> &str { "Leaves every polygon at the top" }
+
+        fn build(&self, nodes: &IndexedNodes) -> IndexedArena
+        {
+            let mut ret = Arena::new();
+            let root = ret.insert((-1, Default::default()));
+
+            for node in nodes {
+                let id = ret.insert(node.value().clone());
+
+                ret.adopt(root, id).unwrap();
+            }
+
+            ret
+        }
+    }
+
+    fn generate_nodes() -> IndexedNodes
+    {
+        [(0., 10.), (1., 2.)]
+            .into_iter()
+            .enumerate()
+            .map(
+                |(i, (x, side))| {
+                    let polygon =
+                        Rectangle::square(Point { x, y: x }, side).polygon();
+
+                    Node::new((i as isize, polygon))
+                }
+            )
+            .collect()
+    }
+
+    fn parents(arena: &IndexedArena) -> Vec<isize>
+    {
+        (1..arena.len())
+            .map(|id| arena.value(arena.parent(id).unwrap()).0)
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin()
+    {
+        let testing = Registry::builtin();
+        let nodes = generate_nodes();
+
+        let names = testing.iter().map(|a| a.name()).collect::<Vec<_>>();
+
+        let expected: &[&str] = &[
+            #[cfg(feature = "frames")] "frames",
+            #[cfg(feature = "naive")] "naive",
+            #[cfg(feature = "quadtree")] "quadtree"
+        ];
+
+        assert_eq!(names, expected);
+
+        for algorithm in testing.iter() {
+            assert_eq!(parents(&algorithm.build(&nodes)), vec! [-1, 0]);
+        }
+    }
+
+    // A polygon with enough corners to be prepared, around a square and
+    // beside a triangle.
+
+    #[test]
+    fn test_prepared()
+    {
+        let corner_count = 2 * PREPARED_MIN_LEN;
+
+        let circle =
+            Any {
+                points:
+                    (0..corner_count)
+                        .map(|i| (i as f64 / corner_count as f64) * TAU)
+                        .map(|a| Point { x: 9. * a.cos(), y: 9. * a.sin() })
+                        .collect()
+            };
+
+        let triangle =
+            Any {
+                points: vec! [
+                    Point { x: 20., y: 0. },
+                    Point { x: 21., y: 0. },
+                    Point { x: 20., y: 1. }
+                ]
+            };
+
+        let square = Rectangle::square(Point { x: -1., y: -1. }, 2.).polygon();
+
+        let nodes =
+            [square, circle, triangle]
+                .into_iter()
+                .enumerate()
+                .map(|(i, polygon)| Node::new((i as isize, polygon)))
+                .collect::<IndexedNodes>();
+
+        for algorithm in Registry::builtin().iter() {
+            let arena = algorithm.build(&nodes);
+            let name = algorithm.name();
+
+            assert_eq!(parents(&arena), vec! [1, -1, -1], "{}", name);
+            assert_eq!(arena.value(2).1.len(), corner_count);
+        }
+    }
+
+    #[test]
+    fn test_register()
+    {
+        let mut testing = Registry::builtin();
+        let count = testing.len();
+
+        testing.register(Flat);
+        testing.register(Flat);
+
+        assert_eq!(testing.len(), count + 1);
+        assert!(testing.get("unknown").is_none());
+
+        let flat = testing.get("flat").unwrap();
+        let (arena, stats) = flat.build_with_stats(&generate_nodes());
+
+        assert_eq!(parents(&arena), vec! [-1, -1]);
+        assert_eq!(stats, ProcessStats::default());
+    }
+}
@@ -0,0 +1,544 @@
+use super::algorithm::HierarchyAlgorithm;
+use super::generate::{generate, GenerateError, Parameters};
+
+use super::super::geo::Unit;
+use super::super::tree::Node;
+use super::super::IndexedNodes;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const COLORS: [&str; 4] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd"];
+
+const HEIGHT: Unit = 400.;
+const MARGIN: Unit = 60.;
+const WIDTH: Unit = 640.;
+
+/***********/
+/* STATICS */
+/***********/
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Measure
+{
+    pub peak: Option<usize>,
+    pub tests: usize,
+    pub time: Duration
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sample
+{
+    pub algorithm: String,
+    pub measure: Measure,
+    pub size: usize
+}
+
+// An allocator keeping track of the allocated bytes and of their peak, which
+// only takes effect once declared as the `#[global_allocator]` of a binary.
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Tracking;
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8
+    {
+        let ret = System.alloc(layout);
+
+        if !ret.is_null() {
+            grow(layout.size());
+        }
+
+        ret
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8
+    {
+        let ret = System.alloc_zeroed(layout);
+
+        if !ret.is_null() {
+            grow(layout.size());
+        }
+
+        ret
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize)
+        -> *mut u8
+    {
+        let ret = System.realloc(ptr, layout, new_size);
+
+        if !ret.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+
+        ret
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// The time of a sample is the median over the runs, and its peak the maximum.
+// Containment tests are the ones reported by the algorithm itself.
+
+pub fn bench(
+    algorithms: &[&dyn HierarchyAlgorithm],
+    inputs: &[IndexedNodes],
+    runs: usize
+) -> Vec<Sample>
+{
+    let mut ret = Vec::new();
+
+    for &algorithm in algorithms {
+        for nodes in inputs {
+            let mut measures =
+                (0..runs)
+                    .map(
+                        |_| {
+                            let ((_, stats), mut measure) =
+                                measure(|| algorithm.build_with_stats(nodes));
+
+                            measure.tests = stats.containment_tests;
+
+                            measure
+                        }
+                    )
+                    .collect::<Vec<_>>();
+
+            measures.sort_by_key(|measure| measure.time);
+
+            let mut measure = measures[measures.len() / 2];
+
+            measure.peak =
+                measures.iter().filter_map(|measure| measure.peak).max();
+
+            ret.push(
+                Sample {
+                    algorithm: algorithm.name().to_owned(),
+                    measure,
+                    size: nodes.len()
+                }
+            );
+        }
+    }
+
+    ret
+}
+
+// An input of each size generated from `parameters` with a constant density,
+// about one polygon for each square of side `4 * radius`.
+
+pub fn generate_inputs(parameters: &Parameters, sizes: &[usize], seed: u64)
+    -> Result<Vec<IndexedNodes>, GenerateError>
+{
+    sizes
+        .iter()
+        .map(
+            |&polygon_count| {
+                let dimension =
+                    4. * parameters.radius * (polygon_count as Unit).sqrt();
+
+                let parameters =
+                    Parameters { dimension, polygon_count, ..*parameters };
+
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+                let nodes =
+                    generate(&parameters, &mut rng)?
+                        .polygons
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, polygon)| Node::new((i as isize, polygon)))
+                        .collect();
+
+                Ok(nodes)
+            }
+        )
+        .collect()
+}
+
+// The peak is relative to the bytes allocated when `f` starts, and is only
+// known with the `tracking` feature, which makes `Tracking` the global
+// allocator of the binary. The containment tests are left for the caller to
+// fill in from `ProcessStats`.
+
+pub fn measure<T, F>(f: F) -> (T, Measure) where F: FnOnce() -> T
+{
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+
+    PEAK.store(allocated, Ordering::Relaxed);
+
+    let start = Instant::now();
+    let ret = f();
+    let time = start.elapsed();
+
+    let peak =
+        cfg!(feature = "tracking")
+            .then(|| PEAK.load(Ordering::Relaxed).saturating_sub(allocated));
+
+    (ret, Measure { peak, tests: 0, time })
+}
+
+// The least-squares slope of `log(time)` against `log(size)`, leaving out the
+// samples of null size or time, whose logarithm is infinite.
+
+pub fn fit_exponent(samples: &[Sample]) -> Option<Unit>
+{
+    let points =
+        samples
+            .iter()
+            .filter(|sample| sample.size > 0 && !sample.measure.time.is_zero())
+            .map(
+                |sample| {
+                    let x = (sample.size as Unit).ln();
+                    let y = sample.measure.time.as_secs_f64().ln();
+
+                    (x, y)
+                }
+            )
+            .collect::<Vec<_>>();
+
+    let n = points.len() as Unit;
+    let mean_x = points.iter().map(|p| p.0).sum::<Unit>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<Unit>() / n;
+
+    let covariance =
+        points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<Unit>();
+
+    let variance =
+        points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<Unit>();
+
+    if variance > 0. {
+        Some(covariance / variance)
+    } else {
+        None
+    }
+}
+
+// The peak is left empty when it is unknown.
+
+pub fn to_csv(samples: &[Sample]) -> String
+{
+    let mut ret =
+        String::from("algorithm,size,time_ns,containment_tests,peak_bytes\n");
+
+    for sample in samples {
+        ret +=
+            &format!(
+                "{},{},{},{},{}\n",
+                sample.algorithm,
+                sample.size,
+                sample.measure.time.as_nanos(),
+                sample.measure.tests,
+                sample.measure.peak.map_or_else(String::new, |x| x.to_string())
+            );
+    }
+
+    ret
+}
+
+// A table of the samples followed by the growth of the time of each algorithm,
+// the peaks being printed as `-` when they are unknown.
+
+pub fn to_report(samples: &[Sample]) -> String
+{
+    let mut ret =
+        format!(
+            "{:<10} {:>8} {:>12} {:>12} {:>12}\n",
+            "algorithm",
+            "size",
+            "time (ms)",
+            "tests",
+            "peak (KiB)"
+        );
+
+    for sample in samples {
+        let peak =
+            sample
+                .measure
+                .peak
+                .map_or_else(|| String::from("-"), |x| (x / 1024).to_string());
+
+        ret +=
+            &format!(
+                "{:<10} {:>8} {:>12.3} {:>12} {:>12}\n",
+                sample.algorithm,
+                sample.size,
+                sample.measure.time.as_secs_f64() * 1e3,
+                sample.measure.tests,
+                peak
+            );
+    }
+
+    for algorithm in algorithms_of(samples) {
+        let samples =
+            samples
+                .iter()
+                .filter(|sample| sample.algorithm == algorithm)
+                .cloned()
+                .collect::<Vec<_>>();
+
+        ret +=
+            &match fit_exponent(&samples) {
+                Some(exponent) =>
+                    format!("{}: time grows as n^{:.2}\n", algorithm, exponent),
+                None =>
+                    format!("{}: too few sizes to fit a growth\n", algorithm)
+            };
+    }
+
+    ret
+}
+
+// A log-log chart of the time against the size, with a line per algorithm.
+
+pub fn to_chart(samples: &[Sample]) -> String
+{
+    let log_size = |sample: &Sample| (sample.size as Unit).ln();
+    let log_time = |sample: &Sample| sample.measure.time.as_secs_f64().ln();
+
+    let range = |f: &dyn Fn(&Sample) -> Unit| {
+        let min = samples.iter().map(f).fold(Unit::INFINITY, Unit::min);
+        let max = samples.iter().map(f).fold(Unit::NEG_INFINITY, Unit::max);
+
+        if max > min { (min, max - min) } else { (min - 1., 2.) }
+    };
+
+    let (x0, dx) = range(&log_size);
+    let (y0, dy) = range(&log_time);
+
+    let at = |sample: &Sample| {
+        let x = (log_size(sample) - x0) / dx * (WIDTH - 2. * MARGIN);
+        let y = (log_time(sample) - y0) / dy * (HEIGHT - 2. * MARGIN);
+
+        (MARGIN + x, HEIGHT - MARGIN - y)
+    };
+
+    let mut ret =
+        format!(
+            r#"<svg xmlns="{}" width="{}" height="{}">"#,
+            "http://www.w3.org/2000/svg",
+            WIDTH,
+            HEIGHT
+        );
+
+    ret +=
+        &format!(
+            r#"{}<path d="M {} {} V {} H {}" fill="none" stroke="black" />"#,
+            "\n\t",
+            MARGIN,
+            MARGIN,
+            HEIGHT - MARGIN,
+            WIDTH - MARGIN
+        );
+
+    ret +=
+        &format!(
+            r#"{}<text x="{}" y="{}" text-anchor="middle">size (log)</text>"#,
+            "\n\t",
+            WIDTH / 2.,
+            HEIGHT - MARGIN / 3.
+        );
+
+    ret +=
+        &format!(
+            r#"{}<text transform="translate({} {}) rotate(-90)" {}>{}</text>"#,
+            "\n\t",
+            MARGIN / 3.,
+            HEIGHT / 2.,
+            r#"text-anchor="middle""#,
+            "time (log)"
+        );
+
+    for (i, algorithm) in algorithms_of(samples).iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+
+        let points =
+            samples
+                .iter()
+                .filter(|sample| sample.algorithm == *algorithm)
+                .map(at)
+                .collect::<Vec<_>>();
+
+        let line =
+            points
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+        ret +=
+            &format!(
+                r#"{}<polyline points="{}" fill="none" stroke="{}" />"#,
+                "\n\t",
+                line,
+                color
+            );
+
+        for (x, y) in points {
+            ret +=
+                &format!(
+                    r#"{}<circle cx="{}" cy="{}" r="3" fill="{}" />"#,
+                    "\n\t",
+                    x,
+                    y,
+                    color
+                );
+        }
+
+        ret +=
+            &format!(
+                r#"{}<text x="{}" y="{}" fill="{}">{}</text>"#,
+                "\n\t",
+                MARGIN + 10.,
+                MARGIN + 20. * i as Unit,
+                color,
+                algorithm
+            );
+    }
+
+    ret + "\n</svg>\n"
+}
+
+// The algorithms of `samples` in their order of appearance.
+
+fn algorithms_of(samples: &[Sample]) -> Vec<&str>
+{
+    let mut ret = Vec::new();
+
+    for sample in samples {
+        if !ret.contains(&sample.algorithm.as_str()) {
+            ret.push(sample.algorithm.as_str());
+        }
+    }
+
+    ret
+}
+
+fn grow(size: usize)
+{
+    let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use indoc::indoc;
+
+    fn sample(algorithm: &str, size: usize, nanos: u64) -> Sample
+    {
+        let time = Duration::from_nanos(nanos);
+        let measure = Measure { peak: None, tests: size, time };
+
+        Sample { algorithm: algorithm.to_owned(), measure, size }
+    }
+
+    #[test]
+    fn test_fit_exponent()
+    {
+        let samples =
+            [100, 200, 400, 800]
+                .map(|size| sample("naive", size, (size * size) as u64));
+
+        let testing = fit_exponent(&samples).unwrap();
+
+        assert!((testing - 2.).abs() < 1e-9);
+        assert_eq!(fit_exponent(&samples[..1]), None);
+
+        let mut with_zero = samples.to_vec();
+
+        with_zero.push(sample("naive", 50, 0));
+
+        assert_eq!(fit_exponent(&with_zero), Some(testing));
+        assert_eq!(fit_exponent(&with_zero[..1]), None);
+        assert_eq!(fit_exponent(&with_zero[3..]), None);
+    }
+
+    #[test]
+    fn test_to_csv()
+    {
+        let expected = indoc! {"
+            algorithm,size,time_ns,containment_tests,peak_bytes
+            frames,10,1500,10,
+            naive,10,2500,10,4096
+        "};
+
+        let mut tracked = sample("naive", 10, 2500);
+
+        tracked.measure.peak = Some(4096);
+
+        assert_eq!(to_csv(&[sample("frames", 10, 1500), tracked]), expected);
+    }
+
+    #[test]
+    fn test_to_report()
+    {
+        let mut samples =
+            [100, 200, 400]
+                .map(|size| sample("naive", size, (size * size) as u64))
+                .to_vec();
+
+        samples.push(sample("frames", 100, 1000));
+        samples[0].measure.peak = Some(4096);
+
+        let testing = to_report(&samples);
+        let lines = testing.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 7);
+        assert!(lines[1].ends_with(" 4"));
+        assert!(lines[2].ends_with(" -"));
+        assert_eq!(lines[5], "naive: time grows as n^2.00");
+        assert_eq!(lines[6], "frames: too few sizes to fit a growth");
+    }
+
+    #[test]
+    fn test_to_chart()
+    {
+        let samples = [
+            sample("frames", 10, 100),
+            sample("frames", 20, 400),
+            sample("naive", 10, 200)
+        ];
+
+        let testing = to_chart(&samples);
+
+        assert_eq!(testing.matches("<polyline").count(), 2);
+        assert_eq!(testing.matches("<circle").count(), 3);
+    }
+}
@@ -0,0 +1,199 @@
+use super::algorithm::HierarchyAlgorithm;
+
+use super::super::geo::polygon::Any;
+use super::super::geo::SVG;
+use super::super::poly::format_unit;
+use super::super::tree::{diff, Diff};
+use super::super::{IndexedArena, IndexedNodes};
+
+use std::fmt;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// The first polygon whose parent differs between two algorithms, with its
+// parent and depth in the result of each one, and the polygons involved.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Disagreement
+{
+    pub diff: Diff,
+    pub id: isize,
+    pub names: [String; 2],
+    pub parents: [(isize, usize); 2],
+    pub polygons: Vec<(isize, Any)>
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        writeln!(
+            f,
+            "{} and {} disagree on polygon {}",
+            self.names[0],
+            self.names[1],
+            self.id
+        )?;
+
+        for (name, (parent, depth)) in self.names.iter().zip(self.parents) {
+            writeln!(f, "\t{}: parent {} at depth {}", name, parent, depth)?;
+        }
+
+        writeln!(
+            f,
+            "{} changed parents, {} moved subtrees, {} depth changes",
+            self.diff.parents.len(),
+            self.diff.subtrees.len(),
+            self.diff.depths.len()
+        )?;
+
+        for (index, polygon) in &self.polygons {
+            for point in polygon.points() {
+                writeln!(
+                    f,
+                    "{} {} {}",
+                    index,
+                    format_unit(point.x),
+                    format_unit(point.y)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SVG for Disagreement {
+    fn to_svg(&self) -> String
+    {
+        let mut ret = String::from("<svg>\n");
+
+        for (_, polygon) in &self.polygons {
+            ret += &format!("\t{}\n", polygon.to_svg());
+        }
+
+        ret + "</svg>\n"
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// Compares the result of each algorithm with the one of the first, and stops
+// at the first disagreement.
+
+pub fn compare(algorithms: &[&dyn HierarchyAlgorithm], nodes: &IndexedNodes)
+    -> Option<Disagreement>
+{
+    let reference = algorithms[0].build(nodes);
+
+    for &algorithm in &algorithms[1..] {
+        let testing = algorithm.build(nodes);
+
+        // Both trees hold the root and every polygon.
+
+        let diff = diff(&reference, &testing).unwrap();
+
+        let Some(&change) = diff.parents.first() else {
+            continue;
+        };
+
+        let id = |arena: &IndexedArena, node: usize| arena.value(node).0;
+        let (before, after) = (change.before.unwrap(), change.after.unwrap());
+
+        let polygons =
+            [change.id, before, after]
+                .into_iter()
+                .filter(|&node| node != 0)
+                .map(|node| reference.value(node).clone())
+                .collect();
+
+        return Some(
+            Disagreement {
+                id: id(&reference, change.id),
+                names: [algorithms[0].name(), algorithm.name()].map(From::from),
+                parents: [
+                    (id(&reference, before), reference.depth(change.id)),
+                    (id(&testing, after), testing.depth(change.id))
+                ],
+                polygons,
+                diff
+            }
+        );
+    }
+
+    None
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "frames", feature = "naive"))]
+mod tests
+{
+    use super::*;
+
+    use super::super::algorithm::Registry;
+
+    use super::super::super::geo::polygon::Rectangle;
+    use super::super::super::geo::{Point, Unit};
+    use super::super::super::tree::{Arena, Node};
+
+    struct Flat;
+
+    impl HierarchyAlgorithm for Flat {
+        fn name(&self) -> &str { "flat" }
+        fn description(&self) -> &str { "Leaves every polygon at the top" }
+
+        fn build(&self, nodes: &IndexedNodes) -> IndexedArena
+        {
+            let mut ret = Arena::new();
+            let root = ret.insert((-1, Default::default()));
+
+            for node in nodes {
+                let id = ret.insert(node.value().clone());
+
+                ret.adopt(root, id).unwrap();
+            }
+
+            ret
+        }
+    }
+
+    fn generate_nodes() -> IndexedNodes
+    {
+        let square = |x: Unit, side: Unit| {
+            Rectangle::square(Point { x, y: x }, side).polygon()
+        };
+
+        [square(0., 10.), square(1., 2.)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, polygon)| Node::new((i as isize, polygon)))
+            .collect()
+    }
+
+    #[test]
+    fn test_compare()
+    {
+        let registry = Registry::builtin();
+        let nodes = generate_nodes();
+
+        let frames = registry.get("frames").unwrap();
+        let naive = registry.get("naive").unwrap();
+
+        assert_eq!(compare(&[frames, naive], &nodes), None);
+
+        let testing = compare(&[frames, naive, &Flat], &nodes).unwrap();
+
+        assert_eq!(testing.id, 1);
+        assert_eq!(testing.names, ["frames", "flat"]);
+        assert_eq!(testing.parents, [(0, 2), (-1, 1)]);
+        assert_eq!(testing.polygons.len(), 2);
+        assert_eq!(testing.to_svg().matches("<polygon").count(), 2);
+    }
+}
@@ -0,0 +1,503 @@
+use super::algorithm::HierarchyAlgorithm;
+use super::process::ProcessStats;
+
+use super::super::geo::polygon::{Any, Polygon};
+use super::super::geo::{Point, Unit};
+use super::super::tree::{Arena, Node};
+use super::super::{IndexedArena, IndexedNodes};
+
+use derive_more::Display;
+
+use std::collections::HashMap;
+use std::error::Error;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// What becomes of polygons which are duplicates of a polygon with a lower id,
+// called their representative:
+//  - `Error` refuses them;
+//  - `Merge` gives them the parent of their representative;
+//  - `NestById` nests them in the order of their ids below it.
+
+#[derive(Clone, Copy, Default)]
+#[derive(Debug, Eq, PartialEq)]
+pub enum DuplicatePolicy { Error, Merge, #[default] NestById }
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+#[display(fmt = "duplicate polygons: {} and {}", first, second)]
+pub struct DuplicateError { pub first: isize, pub second: isize }
+
+/*********/
+/* TYPES */
+/*********/
+
+type Key = [i64; 4];
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Error for DuplicateError {}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// The groups of polygons having the same corners, up to the first corner, the
+// orientation and `tolerance` on each coordinate, or coincident ones, with the
+// same frame and a common side. A group gathers the polygons linked by a chain
+// of such pairs, holds at least two ids, and both the groups and their ids are
+// sorted.
+
+pub fn duplicates(nodes: &IndexedNodes, tolerance: Unit) -> Vec<Vec<isize>>
+{
+    let values = nodes.iter().map(|node| node.value()).collect::<Vec<_>>();
+
+    let keys =
+        values
+            .iter()
+            .map(|value| key_of(&value.1, tolerance))
+            .collect::<Vec<_>>();
+
+    let mut cells = HashMap::<_, Vec<_>>::new();
+
+    for (i, key) in keys.iter().enumerate() {
+        cells.entry(*key).or_default().push(i);
+    }
+
+    // Without tolerance, duplicates have the same key.
+
+    let reach = if tolerance > 0. { 1 } else { 0 };
+    let mut groups = (0..values.len()).collect::<Vec<_>>();
+
+    for (a, key) in keys.iter().enumerate() {
+        for neighbour in neighbours(key, reach) {
+            for &b in cells.get(&neighbour).into_iter().flatten() {
+                let (p, q) = (&values[a].1, &values[b].1);
+
+                if a < b && is_duplicate(p, q, tolerance) {
+                    let (a, b) = (find(&mut groups, a), find(&mut groups, b));
+
+                    groups[a.max(b)] = a.min(b);
+                }
+            }
+        }
+    }
+
+    let mut ret = vec! [Vec::new(); values.len()];
+
+    for (i, value) in values.iter().enumerate() {
+        let group = find(&mut groups, i);
+
+        ret[group].push(value.0);
+    }
+
+    ret.retain(|group| group.len() > 1);
+
+    for group in &mut ret {
+        group.sort_unstable();
+    }
+
+    ret.sort_unstable();
+
+    ret
+}
+
+// Builds the hierarchy of the representatives with `algorithm`, and then adds
+// their duplicates according to `policy`. The nodes of the result follow the
+// order of `nodes`, and the parent of each id does not depend on that order.
+
+pub fn process_with_policy(
+    algorithm: &dyn HierarchyAlgorithm,
+    nodes: &IndexedNodes,
+    policy: DuplicatePolicy,
+    tolerance: Unit
+) -> Result<(IndexedArena, ProcessStats), DuplicateError>
+{
+    let groups = duplicates(nodes, tolerance);
+
+    if let (DuplicatePolicy::Error, Some(group)) = (policy, groups.first()) {
+        return Err(DuplicateError { first: group[0], second: group[1] });
+    }
+
+    // `previous[i]` is the duplicate which `i` is placed relative to, and
+    // `innermost[i]` the last duplicate of the representative `i`.
+
+    let index_of =
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.value().0, i))
+            .collect::<HashMap<_, _>>();
+
+    let mut previous = vec! [None; nodes.len()];
+    let mut innermost = (0..nodes.len()).collect::<Vec<_>>();
+
+    for group in &groups {
+        let indices = group.iter().map(|id| index_of[id]).collect::<Vec<_>>();
+
+        for pair in indices.windows(2) {
+            previous[pair[1]] = match policy {
+                DuplicatePolicy::Merge => Some(indices[0]),
+                _ => Some(pair[0])
+            };
+        }
+
+        if policy == DuplicatePolicy::NestById {
+            innermost[indices[0]] = *indices.last().unwrap();
+        }
+    }
+
+    // The representatives are renumbered from 0 for the algorithms which
+    // index their data with the ids.
+
+    let kept =
+        (0..nodes.len())
+            .filter(|&i| previous[i].is_none())
+            .collect::<Vec<_>>();
+
+    let reduced =
+        kept
+            .iter()
+            .enumerate()
+            .map(|(id, &i)| (id as isize, nodes[i].value().1.clone()))
+            .map(Node::new)
+            .collect::<IndexedNodes>();
+
+    let (tree, stats) = algorithm.build_with_stats(&reduced);
+
+    let mut parents = vec! [None; nodes.len()];
+
+    for node in 1..tree.len() {
+        let parent = tree.value(tree.parent(node).unwrap()).0;
+        let child = kept[tree.value(node).0 as usize];
+
+        parents[child] =
+            (parent >= 0).then(|| innermost[kept[parent as usize]]);
+    }
+
+    for i in 0..nodes.len() {
+        if let Some(j) = previous[i] {
+            parents[i] = match policy {
+                DuplicatePolicy::Merge => parents[j],
+                _ => Some(j)
+            };
+        }
+    }
+
+    let mut ret = Arena::with_capacity(nodes.len() + 1);
+    let root = ret.insert((-1, Any::default()));
+
+    for node in nodes {
+        ret.insert(node.value().clone());
+    }
+
+    for (i, parent) in parents.into_iter().enumerate() {
+        ret.adopt(parent.map_or(root, |parent| parent + 1), i + 1).unwrap();
+    }
+
+    Ok((ret, stats))
+}
+
+fn find(groups: &mut [usize], i: usize) -> usize
+{
+    let mut ret = i;
+
+    while groups[ret] != ret {
+        ret = groups[ret];
+    }
+
+    groups[i] = ret;
+
+    ret
+}
+
+// The smallest and largest coordinates of `polygon`, as `[x0, y0, x1, y1]`.
+
+fn extremes(polygon: &Any) -> [Unit; 4]
+{
+    let (mut min, mut max) = ([Unit::INFINITY; 2], [Unit::NEG_INFINITY; 2]);
+
+    for p in polygon.points() {
+        min = [min[0].min(p.x), min[1].min(p.y)];
+        max = [max[0].max(p.x), max[1].max(p.y)];
+    }
+
+    [min[0], min[1], max[0], max[1]]
+}
+
+// The snapped extremes of `polygon`. The extremes of duplicates are at most
+// `tolerance` apart, so their cells are neighbours on a grid of that side, or
+// equal without tolerance.
+
+fn key_of(polygon: &Any, tolerance: Unit) -> Key
+{
+    let snap = |v: Unit| match tolerance > 0. {
+        true => (v / tolerance).floor() as i64,
+        false => (v + 0.).to_bits() as i64
+    };
+
+    extremes(polygon).map(snap)
+}
+
+// The keys whose cells are at most `reach` away from the cell of `key` on
+// each coordinate.
+
+fn neighbours(key: &Key, reach: i64) -> impl Iterator<Item = Key> + '_
+{
+    let side = 2 * reach + 1;
+
+    (0..side.pow(4)).map(
+        move |k| {
+            let mut ret = *key;
+
+            for (i, c) in ret.iter_mut().enumerate() {
+                *c += k / side.pow(i as u32) % side - reach;
+            }
+
+            ret
+        }
+    )
+}
+
+fn is_close(p: &Point, q: &Point, tolerance: Unit) -> bool
+{
+    (p.x - q.x).abs() <= tolerance && (p.y - q.y).abs() <= tolerance
+}
+
+// Whether `a` and `b` have the same frame and a common side, in which case
+// which one contains the other depends on their first corners.
+
+fn is_coincident(a: &Any, b: &Any, tolerance: Unit) -> bool
+{
+    let is_same_frame =
+        extremes(a)
+            .iter()
+            .zip(extremes(b))
+            .all(|(x, y)| (x - y).abs() <= tolerance);
+
+    let is_common = |(p, q): (&Point, &Point), (r, s): (&Point, &Point)| {
+        (is_close(p, r, tolerance) && is_close(q, s, tolerance)) ||
+            (is_close(p, s, tolerance) && is_close(q, r, tolerance))
+    };
+
+    is_same_frame && sides(a).any(|u| sides(b).any(|v| is_common(u, v)))
+}
+
+fn sides(polygon: &Any) -> impl Iterator<Item = (&Point, &Point)>
+{
+    let points = &polygon.points;
+
+    points.iter().zip(points.iter().cycle().skip(1))
+}
+
+fn is_duplicate(a: &Any, b: &Any, tolerance: Unit) -> bool
+{
+    is_same(a, b, tolerance) || is_coincident(a, b, tolerance)
+}
+
+fn is_same(a: &Any, b: &Any, tolerance: Unit) -> bool
+{
+    let n = a.len();
+    let is_close = |p: &Point, q: &Point| is_close(p, q, tolerance);
+
+    n == b.len() &&
+        (0..n).any(
+            |shift| {
+                let forward =
+                    (0..n).all(
+                        |i| is_close(&a.points[i], &b.points[(i + shift) % n])
+                    );
+
+                let backward =
+                    (0..n).all(
+                        |i| {
+                            let j = (shift + n - i) % n;
+
+                            is_close(&a.points[i], &b.points[j])
+                        }
+                    );
+
+                forward || backward
+            }
+        )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::algorithm::Registry;
+
+    use super::super::super::geo::polygon::Rectangle;
+
+    fn square(x: Unit, side: Unit) -> Any
+    {
+        Rectangle::square(Point { x, y: x }, side).polygon()
+    }
+
+    // An outer square, two copies of a middle one with the second reversed
+    // and shifted, a slightly moved copy of it, and an inner square.
+
+    fn generate_polygons() -> Vec<Any>
+    {
+        let mut reversed = square(1., 8.);
+
+        reversed.points.reverse();
+        reversed.points.rotate_left(1);
+
+        let mut moved = square(1., 8.);
+
+        moved.points[2].x += 1e-6;
+
+        vec! [square(0., 10.), square(1., 8.), reversed, moved, square(2., 1.)]
+    }
+
+    fn generate_nodes(polygons: &[Any], order: &[usize]) -> IndexedNodes
+    {
+        order
+            .iter()
+            .map(|&i| Node::new((i as isize, polygons[i].clone())))
+            .collect()
+    }
+
+    // The parent of each id, whatever the order of the nodes.
+
+    fn parents_by_id(arena: &IndexedArena) -> Vec<isize>
+    {
+        let mut ret = vec! [0; arena.len() - 1];
+
+        for node in 1..arena.len() {
+            let parent = arena.value(arena.parent(node).unwrap()).0;
+
+            ret[arena.value(node).0 as usize] = parent;
+        }
+
+        ret
+    }
+
+    #[test]
+    fn test_duplicates()
+    {
+        let nodes = generate_nodes(&generate_polygons(), &[0, 1, 2, 3, 4]);
+
+        assert_eq!(duplicates(&nodes, 0.), vec! [vec! [1, 2]]);
+        assert_eq!(duplicates(&nodes, 1e-3), vec! [vec! [1, 2, 3]]);
+    }
+
+    // Copies on both sides of a cell border, and squares sharing their left
+    // side.
+
+    #[test]
+    fn test_duplicates_across_cells()
+    {
+        let polygons = [square(0.9, 1.), square(1.1, 1.), square(0.9, 2.)];
+        let nodes = generate_nodes(&polygons, &[0, 1, 2]);
+
+        assert_eq!(duplicates(&nodes, 0.5), vec! [vec! [0, 1]]);
+        assert!(duplicates(&nodes, 0.1).is_empty());
+    }
+
+    // A square, the same square notched from its top side, and a triangle in
+    // the same frame sharing none of its sides.
+
+    #[test]
+    fn test_duplicates_coincident()
+    {
+        let notched =
+            Any {
+                points: vec! [
+                    Point { x: 0., y: 0. },
+                    Point { x: 10., y: 0. },
+                    Point { x: 10., y: 10. },
+                    Point { x: 6., y: 10. },
+                    Point { x: 5., y: 5. },
+                    Point { x: 4., y: 10. },
+                    Point { x: 0., y: 10. }
+                ]
+            };
+
+        let triangle =
+            Any {
+                points: vec! [
+                    Point { x: 0., y: 0. },
+                    Point { x: 10., y: 5. },
+                    Point { x: 5., y: 10. }
+                ]
+            };
+
+        let polygons = [square(0., 10.), notched, triangle];
+        let registry = Registry::builtin();
+
+        let mut parents = Vec::new();
+
+        for order in [[0, 1, 2], [1, 0, 2], [2, 1, 0]] {
+            let nodes = generate_nodes(&polygons, &order);
+
+            assert_eq!(duplicates(&nodes, 0.), vec! [vec! [0, 1]]);
+
+            for algorithm in registry.iter() {
+                let (arena, _) =
+                    process_with_policy(
+                        algorithm,
+                        &nodes,
+                        DuplicatePolicy::NestById,
+                        0.
+                    )
+                    .unwrap();
+
+                parents.push(parents_by_id(&arena));
+            }
+        }
+
+        assert_eq!(parents[0][..2], [-1, 0]);
+        assert!(parents.iter().all(|x| *x == parents[0]));
+    }
+
+    #[test]
+    fn test_policies()
+    {
+        let polygons = generate_polygons();
+        let registry = Registry::builtin();
+
+        let cases = [
+            (DuplicatePolicy::Merge, vec! [-1, 0, 0, 0, 1]),
+            (DuplicatePolicy::NestById, vec! [-1, 0, 1, 2, 3])
+        ];
+
+        let orders = [[0, 1, 2, 3, 4], [4, 3, 2, 1, 0], [2, 4, 0, 3, 1]];
+
+        for algorithm in registry.iter() {
+            for (policy, expected) in &cases {
+                for order in &orders {
+                    let nodes = generate_nodes(&polygons, order);
+
+                    let (arena, _) =
+                        process_with_policy(algorithm, &nodes, *policy, 1e-3)
+                            .unwrap();
+
+                    assert_eq!(&parents_by_id(&arena), expected);
+                }
+            }
+
+            let nodes = generate_nodes(&polygons, &orders[1]);
+
+            let testing =
+                process_with_policy(
+                    algorithm,
+                    &nodes,
+                    DuplicatePolicy::Error,
+                    0.
+                );
+
+            let expected = DuplicateError { first: 1, second: 2 };
+
+            assert_eq!(testing.unwrap_err(), expected);
+        }
+    }
+}
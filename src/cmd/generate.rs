@@ -1,33 +1,262 @@
-use super::super::geo::polygon::Any;
+pub mod adversarial;
+pub use adversarial::{adversarial, Case};
+
+use super::super::geo::polygon::{Any, Rectangle};
 use super::super::geo::{Point, Unit};
-use super::super::geo::{Intersecter, Polygon};
+use super::super::geo::{Container, Distance, Intersecter, Polygon};
 
+use derive_more::Display;
 use rand::Rng;
-use rand::thread_rng;
 
-use std::f64::consts::PI;
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts::{PI, SQRT_2};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const COLLINEAR_NOISE: Unit = 1e-9;
+
+// The smallest radius of a nested polygon, far above the distance under which
+// corners are equal.
+
+const MIN_RADIUS: Unit = 0.01;
+
+const SLIVER_WIDTH: Unit = 0.01;
+const SPIRAL_MIN_CORNERS: usize = 16;
+const SPIRAL_TURNS: Unit = 1.5;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy, Default)]
+#[derive(Debug, Eq, PartialEq)]
+pub enum Family {
+    Convex,
+    Degenerate,
+    Orthogonal,
+    Sliver,
+    Spiral,
+    #[default] Star
+}
+
+#[derive(Copy, Clone)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+pub enum GenerateError
+{
+    #[display(fmt = "invalid attempt budget: at least one attempt is needed")]
+    InvalidAttempts,
+
+    #[display(fmt = "invalid corner count: a polygon has at least 3 corners")]
+    InvalidCornerCount,
+
+    #[display(fmt = "invalid dimension: it must be finite")]
+    InvalidDimension,
+
+    #[display(fmt = "invalid fill: it must be in ]0, 1]")]
+    InvalidFill,
+
+    #[display(fmt = "invalid radius: it must be finite and at least 1")]
+    InvalidRadius,
+
+    #[display(fmt = "too few corners: this family needs at least {}", _0)]
+    TooFewCorners(usize),
+
+    #[display(
+        fmt = "nesting too deep: radius * fill^depth is below {}",
+        MIN_RADIUS
+    )]
+    NestingTooDeep,
+
+    #[display(fmt = "radius too large: the frame is narrower than a diameter")]
+    RadiusTooLarge
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Generation
+{
+    pub parents: Vec<isize>,
+    pub polygons: Vec<Any>,
+    pub report: Report
+}
+
+#[derive(Clone, Copy)]
+#[derive(Debug, PartialEq)]
+pub struct Nesting { pub branching: usize, pub depth: usize, pub fill: Unit }
+
+#[derive(Clone, Copy)]
+#[derive(Debug, PartialEq)]
+pub struct Parameters
+{
+    pub attempts: usize,
+    pub corner_count: usize,
+    pub dimension: Unit,
+    pub family: Family,
+    pub nesting: Nesting,
+    pub polygon_count: usize,
+    pub radius: Unit
+}
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Display)]
+#[display(
+    fmt = "placed {} of {} polygons in {} attempts, missed {} nested ones",
+    placed,
+    requested,
+    attempts,
+    missed
+)]
+pub struct Report
+{
+    pub attempts: usize,
+    pub missed: usize,
+    pub placed: usize,
+    pub requested: usize
+}
+
+// A spatial hash of frames. Cells are as wide as the largest frame, so that a
+// frame never spans more than 4 of them.
+
+struct Grid { cells: HashMap<(i64, i64), Vec<usize>>, size: Unit }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Error for GenerateError {}
+
+impl Family {
+    // Degenerate polygons need a side with an extra corner, histograms a
+    // column, and spirals enough corners on each side not to cross themselves.
+
+    pub fn min_corner_count(&self) -> usize
+    {
+        match self {
+            Family::Degenerate | Family::Orthogonal => 4,
+            Family::Spiral => 2 * SPIRAL_MIN_CORNERS,
+            _ => 3
+        }
+    }
+}
+
+impl Grid {
+    fn new(size: Unit) -> Self { Self { cells: HashMap::new(), size } }
+
+    fn cells_of(&self, frame: &Rectangle)
+        -> impl Iterator<Item = (i64, i64)>
+    {
+        let cell = |p: Point| {
+            ((p.x / self.size).floor() as i64, (p.y / self.size).floor() as i64)
+        };
+
+        let (xmin, ymin) = cell(frame.bottom_left());
+        let (xmax, ymax) = cell(frame.top_right());
+
+        (xmin..=xmax).flat_map(move |x| (ymin..=ymax).map(move |y| (x, y)))
+    }
+
+    fn insert(&mut self, id: usize, frame: &Rectangle)
+    {
+        for cell in self.cells_of(frame).collect::<Vec<_>>() {
+            self.cells.entry(cell).or_default().push(id);
+        }
+    }
+
+    fn neighbours(&self, frame: &Rectangle) -> Vec<usize>
+    {
+        let mut ret =
+            self
+                .cells_of(frame)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>();
+
+        ret.sort_unstable();
+        ret.dedup();
+
+        ret
+    }
+}
+
+impl Parameters {
+    // Children are at most `fill` times as large as their parent, so the ones
+    // at the deepest level are bound to be too small past some depth.
+
+    pub fn validate(&self) -> Result<(), GenerateError>
+    {
+        let fill = self.nesting.fill;
+        let depth = i32::try_from(self.nesting.depth).unwrap_or(i32::MAX);
+
+        if self.attempts == 0 {
+            Err(GenerateError::InvalidAttempts)
+        } else if self.corner_count < 3 {
+            Err(GenerateError::InvalidCornerCount)
+        } else if self.corner_count < self.family.min_corner_count() {
+            Err(GenerateError::TooFewCorners(self.family.min_corner_count()))
+        } else if !self.dimension.is_finite() {
+            Err(GenerateError::InvalidDimension)
+        } else if !(fill > 0. && fill <= 1.) {
+            Err(GenerateError::InvalidFill)
+        } else if !(self.radius.is_finite() && self.radius >= 1.) {
+            Err(GenerateError::InvalidRadius)
+        } else if 2. * self.radius > self.dimension {
+            Err(GenerateError::RadiusTooLarge)
+        } else if self.radius * fill.powi(depth) < MIN_RADIUS {
+            Err(GenerateError::NestingTooDeep)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Report {
+    pub fn is_complete(&self) -> bool
+    {
+        self.placed == self.requested && self.missed == 0
+    }
+}
 
 /*************/
 /* FUNCTIONS */
 /*************/
 
-pub fn generate(
-    corner_count: usize,
-    dimension: Unit,
-    polygon_count: usize,
-    radius: Unit
-) -> Vec<Any>
+// Each polygon gets `attempts` tries to be placed apart from the previous ones,
+// and the generation stops early, with a partial report, when it runs out.
+
+pub fn generate<R>(parameters: &Parameters, rng: &mut R)
+    -> Result<Generation, GenerateError>
+    where R: Rng
 {
-    let mut ret = Vec::new();
-    let mut rng = thread_rng();
+    parameters.validate()?;
+
+    let Parameters {
+        attempts,
+        corner_count,
+        dimension,
+        family,
+        polygon_count,
+        radius,
+        ..
+    } = *parameters;
 
-    ret.reserve(polygon_count);
+    let mut polygons: Vec<Any> = Vec::with_capacity(polygon_count);
+    let mut frames: Vec<Rectangle> = Vec::with_capacity(polygon_count);
+    let mut grid = Grid::new(2. * radius);
 
-    for i in 0..polygon_count {
-        let mut polygon;
+    let mut report =
+        Report { requested: polygon_count, ..Report::default() };
 
-        'polygons : loop {
-            let corner_count = rng.gen_range(3..=corner_count);
+    'polygons: while polygons.len() < polygon_count {
+        for _ in 0..attempts {
+            report.attempts += 1;
+
+            let corner_count =
+                gen_count(rng, family.min_corner_count(), corner_count);
             let radius = rng.gen_range((1.)..=radius);
 
             let center = {
@@ -37,60 +266,736 @@ pub fn generate(
                 Point { x , y }
             };
 
-            polygon = generate_polygon(center, corner_count, radius);
+            let polygon =
+                generate_polygon(
+                    center,
+                    corner_count,
+                    radius,
+                    family,
+                    attempts,
+                    rng
+                );
 
-            if i == 0 {
-                break;
-            } else {
-                for j in &ret[..i] {
-                    if polygon.intersects(j) {
-                        continue 'polygons;
-                    }
-                }
+            let Some(polygon) = polygon else {
+                continue;
+            };
+
+            let Ok(frame) = polygon.frame() else {
+                continue;
+            };
+
+            let is_apart =
+                grid
+                    .neighbours(&frame)
+                    .into_iter()
+                    .all(
+                        |j| {
+                            !frames[j].intersects(&frame) ||
+                                are_apart(&polygons[j], &polygon)
+                        }
+                    );
+
+            if is_apart {
+                grid.insert(polygons.len(), &frame);
+                frames.push(frame);
+                polygons.push(polygon);
+
+                continue 'polygons;
+            }
+        }
+
+        break;
+    }
+
+    report.placed = polygons.len();
+
+    let parents = nest(&mut polygons, parameters, &mut report, rng);
+
+    Ok(Generation { parents, polygons, report })
+}
+
+// Children which find no room in their parent are counted as missed.
+
+fn nest<R>(
+    polygons: &mut Vec<Any>,
+    parameters: &Parameters,
+    report: &mut Report,
+    rng: &mut R
+) -> Vec<isize>
+    where R: Rng
+{
+    let nesting = &parameters.nesting;
 
-                break 'polygons;
+    let mut ret = vec! [-1; polygons.len()];
+    let mut level = (0..polygons.len()).collect::<Vec<_>>();
+
+    for _ in 0..nesting.depth {
+        let mut next = Vec::new();
+
+        for parent in level {
+            let first = polygons.len();
+
+            for _ in 0..nesting.branching {
+                let child =
+                    generate_child(
+                        &polygons[parent],
+                        &polygons[first..],
+                        parameters,
+                        rng
+                    );
+
+                if let Some(child) = child {
+                    next.push(polygons.len());
+                    polygons.push(child);
+                    ret.push(parent as isize);
+                } else {
+                    report.missed += 1;
+                }
             }
         }
 
-        ret.push(polygon);
+        level = next;
     }
 
     ret
 }
 
-fn generate_polygon(center: Point, corner_count: usize, radius: Unit) -> Any
+fn generate_child<R>(
+    parent: &Any,
+    siblings: &[Any],
+    parameters: &Parameters,
+    rng: &mut R
+) -> Option<Any>
+    where R: Rng
 {
-    let mut polygon = Any::default();
-    let mut rng = thread_rng();
+    let frame = parent.frame().ok()?;
+    let (xmin, ymin) = frame.bottom_left().into();
+    let (xmax, ymax) = frame.top_right().into();
 
-    polygon.points.reserve(corner_count);
+    for _ in 0..parameters.attempts {
+        let center = {
+            let x = rng.gen_range(xmin..=xmax);
+            let y = rng.gen_range(ymin..=ymax);
 
-    loop {
-        let mut angles =
-            (0..corner_count)
-                .map(|_| rng.gen_range((0.)..(2. * PI)))
+            Point { x, y }
+        };
+
+        if !parent.contains(&center) {
+            continue;
+        }
+
+        let room =
+            parent
+                .segments()
+                .flatten()
+                .map(|segment| segment.distance_from(&center))
+                .fold(Unit::INFINITY, Unit::min);
+
+        let radius = parameters.nesting.fill * room;
+
+        // Too close to the boundary of the parent to hold a valid polygon.
+
+        if radius < MIN_RADIUS {
+            continue;
+        }
+
+        let corner_count =
+            gen_count(
+                rng,
+                parameters.family.min_corner_count(),
+                parameters.corner_count
+            );
+
+        let child =
+            generate_polygon(
+                center,
+                corner_count,
+                radius,
+                parameters.family,
+                parameters.attempts,
+                rng
+            );
+
+        let Some(child) = child else {
+            continue;
+        };
+
+        let is_inside = parent.contains(&child) && !parent.intersects(&child);
+        let is_apart =
+            siblings.iter().all(|sibling| are_apart(sibling, &child));
+
+        if is_inside && is_apart {
+            return Some(child);
+        }
+    }
+
+    None
+}
+
+fn are_apart(a: &Any, b: &Any) -> bool
+{
+    !a.intersects(b) && !a.contains(b) && !b.contains(a)
+}
+
+// Every family draws its corners within `radius` of `center`, which is what
+// keeps nested children inside their parent, and uses at most `corner_count`
+// of them, which must be at least `Family::min_corner_count`. Gives up after
+// `attempts` invalid polygons.
+
+fn generate_polygon<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    family: Family,
+    attempts: usize,
+    rng: &mut R
+) -> Option<Any>
+    where R: Rng
+{
+    for _ in 0..attempts {
+        let polygon = match family {
+            Family::Convex => gen_convex(center, corner_count, radius, rng),
+            Family::Degenerate => {
+                gen_degenerate(center, corner_count, radius, rng)
+            },
+            Family::Orthogonal => {
+                gen_orthogonal(center, corner_count, radius, rng)
+            },
+            Family::Sliver => gen_sliver(center, corner_count, radius, rng),
+            Family::Spiral => gen_spiral(center, corner_count, radius, rng),
+            Family::Star => gen_star(center, corner_count, radius, rng)
+        };
+
+        if polygon.is_valid() {
+            return Some(polygon);
+        }
+    }
+
+    None
+}
+
+// Corners on a circle, visited by angle.
+
+fn gen_convex<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let distance_to_center = rng.gen_range((radius / 2.)..=radius);
+
+    let points =
+        gen_sorted_directions(rng, corner_count)
+            .into_iter()
+            .map(
+                |(dx, dy)| {
+                    let x = center.x + distance_to_center * dx;
+                    let y = center.y + distance_to_center * dy;
+
+                    Point { x, y }
+                }
+            )
+            .collect();
+
+    Any { points }
+}
+
+// A convex polygon with an extra corner on some of its sides, pushed off the
+// side by a relative distance of at most `COLLINEAR_NOISE`.
+
+fn gen_degenerate<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let base_count = Ord::max(3, corner_count.div_ceil(2));
+    let base = gen_convex(center, base_count, radius, rng);
+    let mut points = Vec::with_capacity(corner_count);
+
+    for (i, segment) in base.segments().flatten().enumerate() {
+        let (a, b) = segment.into();
+
+        points.push(a);
+
+        if i >= corner_count - base_count {
+            continue;
+        }
+
+        let t = rng.gen_range((0.1)..=0.9);
+        let offset = rng.gen_range((-COLLINEAR_NOISE)..=COLLINEAR_NOISE);
+
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+
+        points.push(
+            Point {
+                x: a.x + t * dx - offset * dy,
+                y: a.y + t * dy + offset * dx
+            }
+        );
+    }
+
+    Any { points }
+}
+
+// A histogram: columns of random heights standing on a common base, inscribed
+// in the square of half-diagonal `radius`.
+
+fn gen_orthogonal<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let half_side = radius / SQRT_2;
+    let (left, right) = (center.x - half_side, center.x + half_side);
+    let bottom = center.y - half_side;
+
+    let column_count = Ord::max(1, corner_count.saturating_sub(2) / 2);
+
+    let mut xs =
+        (1..column_count)
+            .map(|_| rng.gen_range(left..=right))
+            .collect::<Vec<_>>();
+
+    xs.sort_by(Unit::total_cmp);
+    xs.insert(0, left);
+    xs.push(right);
+
+    let mut points = vec! [Point { x: left, y: bottom }];
+
+    points.push(Point { x: right, y: bottom });
+
+    for i in (0..column_count).rev() {
+        let y = bottom + rng.gen_range((half_side / 5.)..=(2. * half_side));
+
+        points.push(Point { x: xs[i + 1], y });
+        points.push(Point { x: xs[i], y });
+    }
+
+    Any { points }
+}
+
+// A long and thin polygon: two chains of corners barely off a diameter.
+
+fn gen_sliver<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let (dx, dy) = gen_direction(rng);
+    let length = 0.99 * radius;
+    let width = SLIVER_WIDTH * radius;
+
+    let at = |along: Unit, across: Unit| {
+        Point {
+            x: center.x + along * dx - across * dy,
+            y: center.y + along * dy + across * dx
+        }
+    };
+
+    let mut chain = |count: usize, sign: Unit| {
+        let mut ret =
+            (0..count)
+                .map(|_| rng.gen_range((-length)..length))
                 .collect::<Vec<_>>();
 
-        angles.sort_by(Unit::total_cmp);
+        ret.sort_by(|a, b| (sign * a).total_cmp(&(sign * b)));
+
+        ret
+            .into_iter()
+            .map(|along| (along, sign * rng.gen_range((width / 10.)..=width)))
+            .collect::<Vec<_>>()
+    };
+
+    let inner_count = corner_count.saturating_sub(2);
+    let upper = chain(inner_count.div_ceil(2), 1.);
+    let lower = chain(inner_count / 2, -1.);
+
+    let mut points = vec! [at(-length, 0.)];
+
+    points.extend(upper.into_iter().map(|(along, across)| at(along, across)));
+    points.push(at(length, 0.));
+    points.extend(lower.into_iter().map(|(along, across)| at(along, across)));
+
+    Any { points }
+}
+
+// A thick spiral arm winding `SPIRAL_TURNS` times. Directions are rotated with
+// the rational parametrisation of the circle, so no trigonometry is involved,
+// and each side uses half of the corners.
+
+fn gen_spiral<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let side_count = corner_count / 2;
+    let t = SPIRAL_TURNS * PI / (side_count - 1) as Unit;
+    let (cos, sin) = ((1. - t * t) / (1. + t * t), 2. * t / (1. + t * t));
+
+    let (mut dx, mut dy) = gen_direction(rng);
+    let mut outer = Vec::with_capacity(side_count);
+    let mut inner = Vec::with_capacity(side_count);
+
+    for i in 0..side_count {
+        let distance_to_center =
+            radius * (0.4 + 0.6 * i as Unit / (side_count - 1) as Unit);
+
+        let at = |distance: Unit| {
+            Point {
+                x: center.x + distance * dx,
+                y: center.y + distance * dy
+            }
+        };
+
+        outer.push(at(distance_to_center));
+        inner.push(at(distance_to_center - 0.2 * radius));
+
+        (dx, dy) = (cos * dx - sin * dy, sin * dx + cos * dy);
+    }
+
+    outer.extend(inner.into_iter().rev());
+
+    Any { points: outer }
+}
+
+// Corners at random distances from the centre, visited by angle.
+
+fn gen_star<R>(
+    center: Point,
+    corner_count: usize,
+    radius: Unit,
+    rng: &mut R
+) -> Any
+    where R: Rng
+{
+    let points =
+        gen_sorted_directions(rng, corner_count)
+            .into_iter()
+            .map(
+                |(dx, dy)| {
+                    let distance_to_center = rng.gen_range((0.)..=radius);
+                    let x = center.x + distance_to_center * dx;
+                    let y = center.y + distance_to_center * dy;
+
+                    Point { x, y }
+                }
+            )
+            .collect();
+
+    Any { points }
+}
+
+// `usize` ranges are sampled differently on 32 and 64-bit targets, so counts
+// are always drawn as `u64` to keep a seed portable.
+
+fn gen_count<R>(rng: &mut R, low: usize, high: usize) -> usize where R: Rng
+{
+    rng.gen_range((low as u64)..=(high as u64)) as usize
+}
+
+// A uniformly distributed unit vector obtained by rejection sampling, which
+// only relies on correctly rounded operations (unlike `sin` and `cos`) and thus
+// gives the same result on every platform.
+
+fn gen_direction<R>(rng: &mut R) -> (Unit, Unit) where R: Rng
+{
+    loop {
+        let x = rng.gen_range((-1.)..=1.);
+        let y = rng.gen_range((-1.)..=1.);
+        let norm = Unit::sqrt(x * x + y * y);
+
+        if (norm > 0.) && (norm <= 1.) {
+            return (x / norm, y / norm);
+        }
+    }
+}
+
+fn gen_sorted_directions<R>(rng: &mut R, count: usize) -> Vec<(Unit, Unit)>
+    where R: Rng
+{
+    let mut ret = (0..count).map(|_| gen_direction(rng)).collect::<Vec<_>>();
+
+    ret.sort_by(|a, b| pseudo_angle(a).total_cmp(&pseudo_angle(b)));
+
+    ret
+}
+
+fn pseudo_angle(&(x, y): &(Unit, Unit)) -> Unit
+{
+    let p = y / (x.abs() + y.abs());
+
+    if x < 0. {
+        2. - p
+    } else if y < 0. {
+        4. + p
+    } else {
+        p
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn bits(generation: &Generation) -> Vec<(u64, u64)>
+    {
+        generation
+            .polygons
+            .iter()
+            .flat_map(|polygon| polygon.points())
+            .map(|p| (p.x.to_bits(), p.y.to_bits()))
+            .collect()
+    }
+
+    fn parameters() -> Parameters
+    {
+        Parameters {
+            attempts: 1000,
+            corner_count: 6,
+            dimension: 100.,
+            family: Family::Star,
+            nesting: Nesting { branching: 2, depth: 0, fill: 0.5 },
+            polygon_count: 10,
+            radius: 10.
+        }
+    }
+
+    fn run(parameters: &Parameters, seed: u64) -> Generation
+    {
+        generate(parameters, &mut ChaCha8Rng::seed_from_u64(seed)).unwrap()
+    }
+
+    #[test]
+    fn test_generate_reproducible()
+    {
+        let a = run(&parameters(), 42);
+        let b = run(&parameters(), 42);
+
+        assert_eq!(bits(&a), bits(&b));
+    }
+
+    #[test]
+    fn test_generate_seeds_differ()
+    {
+        let a = run(&parameters(), 1);
+        let b = run(&parameters(), 2);
+
+        assert_ne!(bits(&a), bits(&b));
+    }
+
+    #[test]
+    fn test_generate_apart()
+    {
+        let testing = run(&Parameters { polygon_count: 50, ..parameters() }, 7);
+
+        assert!(testing.report.is_complete());
+        assert_eq!(testing.parents, vec! [-1; 50]);
+
+        for (i, a) in testing.polygons.iter().enumerate() {
+            for b in &testing.polygons[(i + 1)..] {
+                assert!(are_apart(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_budget()
+    {
+        let parameters =
+            Parameters {
+                attempts: 20,
+                dimension: 40.,
+                polygon_count: 1000,
+                ..parameters()
+            };
+
+        let testing = run(&parameters, 42);
+
+        assert!(!testing.report.is_complete());
+        assert_eq!(testing.report.placed, testing.polygons.len());
+        assert_eq!(testing.report.requested, 1000);
+        assert!(testing.report.attempts <= 20 * 1000);
+    }
 
-        for a in angles.iter() {
-            let point = {
-                let distance_to_center = rng.gen_range((0.)..=radius);
-                let x = center.x + distance_to_center * a.cos();
-                let y = center.y + distance_to_center * a.sin();
+    #[cfg(feature = "frames")]
+    #[test]
+    fn test_generate_nested()
+    {
+        use super::super::process::process_frames;
+        use super::super::super::tree::Node;
 
-                Point { x, y }
+        let parameters =
+            Parameters {
+                dimension: 1000.,
+                nesting: Nesting { branching: 2, depth: 3, fill: 0.5 },
+                polygon_count: 5,
+                radius: 50.,
+                ..parameters()
             };
 
-            polygon.points.push(point);
+        let Generation { parents: expected, polygons, .. } =
+            run(&parameters, 42);
+
+        let nodes =
+            polygons
+                .into_iter()
+                .enumerate()
+                .map(|(i, polygon)| Node::new((i as isize, polygon)))
+                .collect();
+
+        let testing = process_frames(&nodes);
+
+        let testing =
+            (1..testing.len())
+                .map(|id| testing.value(testing.parent(id).unwrap()).0)
+                .collect::<Vec<_>>();
+
+        assert!(expected.iter().filter(|&&p| p != -1).count() > 5);
+        assert_eq!(testing, expected);
+    }
+
+    #[test]
+    fn test_validate()
+    {
+        let cases = [
+            (Parameters { attempts: 0, ..parameters() },
+                GenerateError::InvalidAttempts),
+            (Parameters { corner_count: 2, ..parameters() },
+                GenerateError::InvalidCornerCount),
+            (Parameters { dimension: Unit::INFINITY, ..parameters() },
+                GenerateError::InvalidDimension),
+            (Parameters {
+                nesting: Nesting { branching: 2, depth: 1, fill: 1.5 },
+                ..parameters()
+            }, GenerateError::InvalidFill),
+            (Parameters { radius: 0.5, ..parameters() },
+                GenerateError::InvalidRadius),
+            (Parameters { radius: Unit::NAN, ..parameters() },
+                GenerateError::InvalidRadius),
+            (Parameters { radius: 60., ..parameters() },
+                GenerateError::RadiusTooLarge),
+            (Parameters {
+                nesting: Nesting { branching: 1, depth: 11, fill: 0.5 },
+                ..parameters()
+            }, GenerateError::NestingTooDeep),
+            (Parameters {
+                nesting: Nesting { branching: 1, depth: usize::MAX, fill: 0.9 },
+                ..parameters()
+            }, GenerateError::NestingTooDeep),
+            (Parameters { family: Family::Spiral, ..parameters() },
+                GenerateError::TooFewCorners(2 * SPIRAL_MIN_CORNERS))
+        ];
+
+        assert_eq!(parameters().validate(), Ok(()));
+
+        for (testing, expected) in cases {
+            assert_eq!(testing.validate(), Err(expected));
         }
+    }
 
-        if polygon.is_valid() {
-            break;
-        } else {
-            polygon.points.clear();
+    #[test]
+    fn test_generate_polygon_families()
+    {
+        let families = [
+            Family::Convex,
+            Family::Degenerate,
+            Family::Orthogonal,
+            Family::Sliver,
+            Family::Spiral,
+            Family::Star
+        ];
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let center = Point { x: 50., y: 50. };
+        let radius = 10.;
+
+        for family in families {
+            let min = family.min_corner_count();
+            let counts = [min, 8, 9, 40];
+
+            for corner_count in counts.into_iter().filter(|&n| n >= min) {
+                let testing =
+                    generate_polygon(
+                        center,
+                        corner_count,
+                        radius,
+                        family,
+                        1000,
+                        &mut rng
+                    )
+                    .unwrap();
+
+                assert!(testing.is_valid(), "{:?}", family);
+                assert!(testing.len() <= corner_count, "{:?}", family);
+                assert!(
+                    testing
+                        .points()
+                        .all(|p| p.distance_from(&center) <= radius + 1e-9),
+                    "{:?}",
+                    family
+                );
+            }
         }
     }
 
-    return polygon;
+    #[test]
+    fn test_generate_polygon_gives_up()
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let center = Point { x: 50., y: 50. };
+
+        let testing =
+            generate_polygon(center, 5, 1e-17, Family::Star, 100, &mut rng);
+
+        assert_eq!(testing, None);
+    }
+
+    #[test]
+    fn test_generate_missed_children()
+    {
+        let parameters =
+            Parameters {
+                attempts: 5,
+                nesting: Nesting { branching: 40, depth: 1, fill: 1. },
+                polygon_count: 2,
+                ..parameters()
+            };
+
+        let testing = run(&parameters, 42);
+        let children = testing.polygons.len() - testing.report.placed;
+
+        assert!(testing.report.missed > 0);
+        assert_eq!(children + testing.report.missed, 2 * 40);
+        assert!(!testing.report.is_complete());
+    }
+
+    #[test]
+    fn test_pseudo_angle()
+    {
+        let testing =
+            [(1., 0.), (1., 1.), (0., 1.), (-1., 1.), (-1., 0.), (-1., -1.)]
+                .iter()
+                .map(pseudo_angle)
+                .collect::<Vec<_>>();
+
+        assert!(testing.windows(2).all(|w| w[0] < w[1]));
+    }
 }
@@ -0,0 +1,157 @@
+use super::super::super::geo::polygon::Any;
+use super::super::super::geo::{Point, Unit};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+// Above 2^52, consecutive `f64` are one unit apart.
+const PRECISION_LIMIT: Unit = 4503599627370496.;
+
+const SPACING: Unit = 20.;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// `parents` holds, for each polygon of the case, the index of its expected
+// parent within the case, or -1.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Case
+{
+    pub name: &'static str,
+    pub parents: Vec<isize>,
+    pub polygons: Vec<Any>
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// The cases are laid out side by side, so they can share a file without
+// interacting.
+
+pub fn adversarial() -> Vec<Case>
+{
+    let mut ret = vec! [
+        Case {
+            name: "ray-through-vertex",
+            parents: vec! [-1, 0],
+            polygons: vec! [
+                polygon(&[(0., 0.), (10., 0.), (10., 8.), (5., 10.), (0., 8.)]),
+                polygon(&[(5., 5.), (6., 5.), (5.5, 6.)])
+            ]
+        },
+        Case {
+            name: "ray-touching-vertices",
+            parents: vec! [-1, -1],
+            polygons: vec! [
+                polygon(&[(5., 6.), (8., 8.), (5., 10.), (2., 8.)]),
+                polygon(&[(5., 2.), (6., 2.), (5.5, 3.)])
+            ]
+        },
+        Case {
+            name: "ray-along-edge",
+            parents: vec! [-1, 0],
+            polygons: vec! [
+                polygon(
+                    &[
+                        (0., 0.),
+                        (10., 0.),
+                        (10., 10.),
+                        (5., 10.),
+                        (5., 8.),
+                        (0., 8.)
+                    ]
+                ),
+                polygon(&[(5., 3.), (6., 3.), (5.5, 4.)])
+            ]
+        },
+        Case {
+            name: "shared-edge",
+            parents: vec! [-1, 0, 0],
+            polygons: vec! [
+                polygon(&[(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+                polygon(&[(1., 1.), (5., 1.), (5., 9.), (1., 9.)]),
+                polygon(&[(9., 9.), (5., 9.), (5., 1.), (9., 1.)])
+            ]
+        },
+        Case {
+            name: "frame-but-not-polygon",
+            parents: vec! [-1, -1],
+            polygons: vec! [
+                polygon(
+                    &[
+                        (0., 0.),
+                        (10., 0.),
+                        (10., 3.),
+                        (3., 3.),
+                        (3., 10.),
+                        (0., 10.)
+                    ]
+                ),
+                polygon(&[(6., 6.), (9., 6.), (7.5, 9.)])
+            ]
+        },
+        Case {
+            name: "identical-frames",
+            parents: vec! [-1, -1],
+            polygons: vec! [
+                polygon(&[(0., 0.), (10., 10.), (0., 10.)]),
+                polygon(&[(0., 0.), (10., 0.), (10., 10.)])
+            ]
+        }
+    ];
+
+    for (i, case) in ret.iter_mut().enumerate() {
+        translate(&mut case.polygons, SPACING * i as Unit, 0.);
+    }
+
+    let mut precision =
+        Case {
+            name: "precision-limit",
+            parents: vec! [-1, 0],
+            polygons: vec! [
+                polygon(&[(0., 0.), (16., 0.), (16., 16.), (0., 16.)]),
+                polygon(&[(1., 1.), (15., 1.), (15., 15.), (1., 15.)])
+            ]
+        };
+
+    translate(&mut precision.polygons, PRECISION_LIMIT, PRECISION_LIMIT);
+    ret.push(precision);
+
+    ret
+}
+
+fn polygon(points: &[(Unit, Unit)]) -> Any
+{
+    Any { points: points.iter().map(|&(x, y)| Point { x, y }).collect() }
+}
+
+fn translate(polygons: &mut [Any], dx: Unit, dy: Unit)
+{
+    for point in polygons.iter_mut().flat_map(|polygon| &mut polygon.points) {
+        point.x += dx;
+        point.y += dy;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::super::super::geo::Polygon;
+
+    #[test]
+    fn test_adversarial()
+    {
+        for case in adversarial() {
+            let (name, count) = (case.name, case.polygons.len());
+
+            assert_eq!(case.parents.len(), count, "{}", name);
+            assert!(case.polygons.iter().all(Polygon::is_valid), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::super::IndexedArena;
+
+use super::super::geo::{Container, Point};
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// Siblings are disjoint, so the innermost polygon containing `point` is found
+// by descending into the only child containing it, level after level.
+
+pub fn locate<P>(arena: &IndexedArena<P>, point: &Point) -> Option<isize>
+    where P: Container<Point>
+{
+    let mut ret = None;
+    let mut current = 0;
+
+    while let Some(child) =
+        arena
+            .children(current)
+            .find(|&child| arena.value(child).1.contains(point))
+    {
+        ret = Some(arena.value(child).0);
+        current = child;
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::super::geo::polygon::{Any, Rectangle};
+    use super::super::super::tree::Arena;
+
+    fn generate_arena() -> IndexedArena
+    {
+        let square = |x: f64, side: f64| {
+            Rectangle::square(Point { x, y: x }, side).polygon()
+        };
+
+        let mut ret = Arena::new();
+
+        let root = ret.insert((-1, Any::default()));
+        let a = ret.insert((0, square(0., 10.)));
+        let b = ret.insert((1, square(2., 6.)));
+        let c = ret.insert((2, square(4., 2.)));
+        let d = ret.insert((3, square(20., 1.)));
+        let e = ret.insert((4, square(-20., 5.)));
+
+        ret.adopt(root, a).unwrap();
+        ret.adopt(a, b).unwrap();
+        ret.adopt(b, c).unwrap();
+        ret.adopt(root, d).unwrap();
+        ret.adopt(root, e).unwrap();
+
+        ret
+    }
+
+    #[test]
+    fn test_locate()
+    {
+        let arena = generate_arena();
+
+        let testing =
+            [(1., 1.), (3., 3.), (5., 5.), (20.5, 20.5), (-18., -16.)]
+                .map(|(x, y)| locate(&arena, &Point { x, y }));
+
+        assert_eq!(testing, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(locate(&arena, &Point { x: 15., y: 15. }), None);
+    }
+}
@@ -0,0 +1,191 @@
+use super::super::IndexedNodes;
+
+use super::super::geo::polygon::{Polygon, Rectangle};
+use super::super::geo::{Container, Intersecter};
+
+use std::cell::Ref;
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// The pairs of polygons whose boundaries meet, that is the ones which neither
+// are disjoint nor nested. Each pair is ordered and given by polygon ids.
+
+pub fn overlaps<P>(nodes: &IndexedNodes<P>) -> Vec<(isize, isize)>
+    where P: Intersecter + Polygon
+{
+    let values = nodes.iter().map(|node| node.value()).collect::<Vec<_>>();
+
+    let mut ret =
+        frame_pairs(&values)
+            .into_iter()
+            .filter(|&(a, b)| values[a].1.intersects(&values[b].1))
+            .map(|(a, b)| sorted(values[a].0, values[b].0))
+            .collect::<Vec<_>>();
+
+    ret.sort_unstable();
+
+    ret
+}
+
+// Containment as a partial order: the parents of a polygon are all the
+// polygons containing it which contain no other of its containers, so a
+// polygon lying in several overlapping ones has several parents. `ret[i]`
+// holds the sorted ids of the parents of `nodes[i]`.
+
+pub fn containment_dag<P>(nodes: &IndexedNodes<P>) -> Vec<Vec<isize>>
+    where P: Container + Intersecter + Polygon
+{
+    let values = nodes.iter().map(|node| node.value()).collect::<Vec<_>>();
+
+    let is_inside = |a: usize, b: usize| {
+        let (a, b) = (&values[a].1, &values[b].1);
+
+        b.contains(a) && !b.intersects(a)
+    };
+
+    let mut containers = vec! [Vec::new(); values.len()];
+
+    for (a, b) in frame_pairs(&values) {
+        if is_inside(a, b) {
+            containers[a].push(b);
+        } else if is_inside(b, a) {
+            containers[b].push(a);
+        }
+    }
+
+    containers
+        .iter()
+        .map(
+            |list| {
+                let mut parents =
+                    list
+                        .iter()
+                        .filter(
+                            |&&c| {
+                                !list
+                                    .iter()
+                                    .any(|&d| containers[d].contains(&c))
+                            }
+                        )
+                        .map(|&c| values[c].0)
+                        .collect::<Vec<_>>();
+
+                parents.sort_unstable();
+
+                parents
+            }
+        )
+        .collect()
+}
+
+// The pairs of indices whose frames intersect, found by sweeping the frames
+// from left to right. The polygons without a frame are left out.
+
+fn frame_pairs<P>(values: &[Ref<'_, (isize, P)>]) -> Vec<(usize, usize)>
+    where P: Polygon
+{
+    let mut frames =
+        values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| Some((i, value.1.frame().ok()?)))
+            .collect::<Vec<(usize, Rectangle)>>();
+
+    frames.sort_by(|(_, a), (_, b)| {
+        a.bottom_left().x.total_cmp(&b.bottom_left().x)
+    });
+
+    let mut active = Vec::<(usize, Rectangle)>::new();
+    let mut ret = Vec::new();
+
+    for (i, frame) in frames {
+        let left = frame.bottom_left().x;
+
+        active.retain(|(_, other)| other.top_right().x >= left);
+
+        for &(j, other) in &active {
+            if frame.intersects(&other) {
+                ret.push(sorted(i, j));
+            }
+        }
+
+        active.push((i, frame));
+    }
+
+    ret
+}
+
+fn sorted<T>(a: T, b: T) -> (T, T) where T: Ord
+{
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::super::geo::polygon::Any;
+    use super::super::super::geo::Point;
+    use super::super::super::tree::Node;
+
+    fn square(x: f64, y: f64, side: f64) -> Any
+    {
+        Rectangle::square(Point { x, y }, side).polygon()
+    }
+
+    fn generate_nodes(polygons: Vec<Any>) -> IndexedNodes
+    {
+        polygons
+            .into_iter()
+            .enumerate()
+            .map(|(i, polygon)| Node::new((i as isize, polygon)))
+            .collect()
+    }
+
+    #[test]
+    fn test_overlaps()
+    {
+        let nodes =
+            generate_nodes(
+                vec! [
+                    square(0., 0., 10.),
+                    square(5., 5., 10.),
+                    square(6., 6., 2.),
+                    square(20., 0., 10.),
+                    square(21., 1., 2.)
+                ]
+            );
+
+        assert_eq!(overlaps(&nodes), vec! [(0, 1)]);
+    }
+
+    #[test]
+    fn test_containment_dag()
+    {
+        let nodes =
+            generate_nodes(
+                vec! [
+                    square(0., 0., 10.),
+                    square(5., 5., 10.),
+                    square(6., 6., 2.),
+                    square(20., 0., 10.),
+                    square(21., 1., 5.),
+                    square(22., 2., 1.)
+                ]
+            );
+
+        let expected = vec! [
+            vec! [],
+            vec! [],
+            vec! [0, 1],
+            vec! [],
+            vec! [3],
+            vec! [4]
+        ];
+
+        assert_eq!(containment_dag(&nodes), expected);
+    }
+}
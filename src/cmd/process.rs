@@ -1,99 +1,501 @@
-use super::super::tree::Node;
-use super::super::{IndexedNode, IndexedNodes};
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+use super::super::tree::Arena;
 
-use super::super::geo::polygon::Any;
-use super::super::geo::Container;
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+use super::super::{IndexedArena, IndexedNodes};
 
-#[cfg(feature = "frames")] use super::super::geo::Polygon;
-#[cfg(feature = "frames")] use super::super::geo::polygon::Rectangle;
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+use super::super::geo::{polygon::Prepare, Container};
 
-use std::rc::Rc;
+#[cfg(any(feature = "frames", feature = "quadtree"))]
+use super::super::geo::{polygon::Rectangle, Polygon};
+
+#[cfg(feature = "quadtree")] use super::super::geo::Quadtree;
+
+use derive_more::Display;
+
+#[cfg(any(feature = "frames", feature = "naive"))]
+use std::collections::VecDeque;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy, Default)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+#[display(
+    fmt = "queue pops: {}\nfilter calls: {}\nframe rejections: {}\n\
+           containment tests: {}\nreparents: {}",
+    queue_pops,
+    filter_calls,
+    frame_rejections,
+    containment_tests,
+    reparents
+)]
+pub struct ProcessStats
+{
+    pub containment_tests: usize,
+    pub filter_calls: usize,
+    pub frame_rejections: usize,
+    pub queue_pops: usize,
+    pub reparents: usize
+}
 
 /*************/
 /* FUNCTIONS */
 /*************/
 
+// The polygons are prepared while the hierarchy is built (see `Prepare`), so
+// that the ones with many corners are tested through an index.
+
 #[cfg(feature = "frames")]
-pub fn process_frames(nodes: &IndexedNodes) -> IndexedNode
+pub fn process_frames<P>(nodes: &IndexedNodes<P>) -> IndexedArena<P>
+    where P: Clone + Prepare
 {
-    let ret = Node::new((-1, Any::default()));
+    process_frames_with_stats(nodes).0
+}
 
-    let frames = generate_frames(nodes);
+#[cfg(feature = "frames")]
+pub fn process_frames_with_stats<P>(nodes: &IndexedNodes<P>)
+    -> (IndexedArena<P>, ProcessStats)
+    where P: Clone + Prepare
+{
+    let mut ret = generate_arena(nodes);
 
-    for node in nodes {
-        ret.adopt(node);
-    }
+    let frames = generate_frames(&ret);
 
-    build_tree_from_polygons(nodes, |s, b| contains(&frames, b, s));
+    let stats =
+        build_tree_from_polygons(
+            &mut ret,
+            |s, b, stats| contains(&frames, b, s, stats)
+        );
 
-    ret
+    (restore_arena(ret), stats)
 }
 
 #[cfg(feature = "naive")]
-pub fn process_naive(nodes: &IndexedNodes) -> IndexedNode
+pub fn process_naive<P>(nodes: &IndexedNodes<P>) -> IndexedArena<P>
+    where P: Clone + Prepare
 {
-    let ret = Node::new((-1, Any::default()));
+    process_naive_with_stats(nodes).0
+}
 
-    for node in nodes {
-        ret.adopt(node);
-    }
+#[cfg(feature = "naive")]
+pub fn process_naive_with_stats<P>(nodes: &IndexedNodes<P>)
+    -> (IndexedArena<P>, ProcessStats)
+    where P: Clone + Prepare
+{
+    let mut ret = generate_arena(nodes);
 
-    build_tree_from_polygons(nodes, |s, b| b.value().1.contains(&s.value().1));
+    let stats =
+        build_tree_from_polygons(
+            &mut ret,
+            |s, b, stats| {
+                stats.containment_tests += 1;
 
-    ret
+                b.1.contains(&s.1)
+            }
+        );
+
+    (restore_arena(ret), stats)
 }
 
-fn build_tree_from_polygons<F>(nodes: &IndexedNodes, filter: F)
-    where F: Fn(&IndexedNode, &IndexedNode) -> bool
+#[cfg(feature = "quadtree")]
+pub fn process_quadtree<P>(nodes: &IndexedNodes<P>) -> IndexedArena<P>
+    where P: Clone + Prepare
 {
-    use std::collections::VecDeque;
+    process_quadtree_with_stats(nodes).0
+}
 
-    let mut placement_queue = VecDeque::new();
+// Each polygon is only tested against the polygons whose frames meet its own
+// in the quadtree, and goes below the innermost of those containing it, that
+// is the one with the smallest frame. The polygons without a frame stay below
+// the root.
 
-    for node in nodes {
-        placement_queue.push_back(node.clone());
-    }
+#[cfg(feature = "quadtree")]
+pub fn process_quadtree_with_stats<P>(nodes: &IndexedNodes<P>)
+    -> (IndexedArena<P>, ProcessStats)
+    where P: Clone + Prepare
+{
+    let mut ret = generate_arena(nodes);
+    let mut stats = ProcessStats::default();
+
+    let frames =
+        generate_frames(&ret)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, frame)| Some((i, frame?)))
+            .collect::<Vec<_>>();
+
+    let bounds = frames.iter().map(|&(_, frame)| frame);
+
+    let Some(quadtree) = Quadtree::from_frames(bounds) else {
+        return (restore_arena(ret), stats);
+    };
 
-    while !placement_queue.is_empty() {
-        let selected = placement_queue.pop_front().unwrap();
-        let parent = selected.parent().unwrap();
-        let brothers = parent.children().clone();
+    for &(i, frame) in &frames {
+        let polygon = &ret.value(i + 1).1;
 
-        let brothers =
-            brothers
+        let parent =
+            quadtree
+                .query(&frame)
                 .into_iter()
-                .filter(|child| !Rc::ptr_eq(child, &selected))
-                .map(|brother| (selected.clone(), brother))
-                .filter(|(s, b)| filter(s, b));
+                .map(|k| frames[k])
+                .filter(|&(j, _)| j != i)
+                .filter(
+                    |&(j, other)| {
+                        stats.filter_calls += 1;
+
+                        if other.contains(&frame) {
+                            stats.containment_tests += 1;
+
+                            ret.value(j + 1).1.contains(polygon)
+                        } else {
+                            stats.frame_rejections += 1;
+
+                            false
+                        }
+                    }
+                )
+                .min_by(|&(a, x), &(b, y)| {
+                    let area = |r: Rectangle| r.width() * r.height();
 
-        for (s, b) in brothers {
-            b.adopt(&s);
+                    area(x).total_cmp(&area(y)).then(a.cmp(&b))
+                })
+                .map(|(j, _)| j);
 
-            placement_queue.push_back(s);
+        if let Some(parent) = parent {
+            stats.reparents += 1;
 
-            break;
+            ret.adopt(parent + 1, i + 1).unwrap();
         }
     }
+
+    (restore_arena(ret), stats)
 }
 
-#[cfg(feature = "frames")]
-fn contains(frames: &Vec<Rectangle>, a: &IndexedNode, b: &IndexedNode) -> bool
+#[cfg(any(feature = "frames", feature = "naive"))]
+fn build_tree_from_polygons<P, F>(arena: &mut IndexedArena<P>, filter: F)
+    -> ProcessStats
+    where F: Fn(&(isize, P), &(isize, P), &mut ProcessStats) -> bool
 {
-    let a = a.value();
-    let b = b.value();
+    let mut ret = ProcessStats::default();
+    let mut placement_queue = (1..arena.len()).collect::<VecDeque<_>>();
 
-    let is_frame_contained =
-        frames[a.0 as usize].contains(&frames[b.0 as usize]);
+    while let Some(selected) = placement_queue.pop_front() {
+        ret.queue_pops += 1;
+
+        let parent = arena.parent(selected).unwrap();
+
+        let brother =
+            arena
+                .children(parent)
+                .filter(|&child| child != selected)
+                .find(
+                    |&brother| {
+                        ret.filter_calls += 1;
+
+                        filter(
+                            arena.value(selected),
+                            arena.value(brother),
+                            &mut ret
+                        )
+                    }
+                );
+
+        if let Some(brother) = brother {
+            ret.reparents += 1;
+
+            arena.adopt(brother, selected).unwrap();
 
-    is_frame_contained && a.1.contains(&b.1)
+            placement_queue.push_back(selected);
+        }
+    }
+
+    ret
 }
 
+// A polygon without a frame neither contains nor is contained.
+
 #[cfg(feature = "frames")]
-fn generate_frames(nodes: &IndexedNodes) -> Vec<Rectangle>
+fn contains<P>(
+    frames: &[Option<Rectangle>],
+    a: &(isize, P),
+    b: &(isize, P),
+    stats: &mut ProcessStats
+) -> bool
+    where P: Container
+{
+    let is_frame_contained =
+        match (&frames[a.0 as usize], &frames[b.0 as usize]) {
+            (Some(a), Some(b)) => a.contains(b),
+            _ => false
+        };
+
+    if is_frame_contained {
+        stats.containment_tests += 1;
+
+        a.1.contains(&b.1)
+    } else {
+        stats.frame_rejections += 1;
+
+        false
+    }
+}
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+fn generate_arena<P>(nodes: &IndexedNodes<P>) -> IndexedArena<P::Prepared>
+    where P: Clone + Prepare
+{
+    let mut ret = Arena::with_capacity(nodes.len() + 1);
+
+    let root = ret.insert((-1, Default::default()));
+
+    for node in nodes {
+        let (id, polygon) = node.value().clone();
+        let id = ret.insert((id, polygon.prepare()));
+
+        ret.adopt(root, id).unwrap();
+    }
+
+    ret
+}
+
+// The frames of the polygons of `arena` but its root, in order.
+
+#[cfg(any(feature = "frames", feature = "quadtree"))]
+fn generate_frames<P>(arena: &IndexedArena<P>) -> Vec<Option<Rectangle>>
+    where P: Polygon
+{
+    (1..arena.len()).map(|id| arena.value(id).1.frame().ok()).collect()
+}
+
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+fn restore_arena<P>(arena: IndexedArena<P::Prepared>) -> IndexedArena<P>
+    where P: Prepare
 {
-    nodes
-        .iter()
-        .map(|node| node.value())
-        .map(|item| item.1.frame())
-        .collect()
+    arena.map(|(id, polygon)| (id, P::restore(polygon)))
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+mod tests
+{
+    use super::*;
+
+    use super::super::generate::adversarial;
+
+    #[cfg(all(feature = "frames", feature = "quadtree"))]
+    use super::super::generate::{generate, Family, Nesting, Parameters};
+
+    use super::super::super::geo::Point;
+    use super::super::super::geo::polygon::{Any, Circle, Rectangle, Shape};
+    use super::super::super::tree::Node;
+
+    fn generate_cases() -> Vec<(&'static str, IndexedNodes<Any>, Vec<isize>)>
+    {
+        adversarial()
+            .into_iter()
+            .map(
+                |case| {
+                    let nodes =
+                        case
+                            .polygons
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, p)| Node::new((i as isize, p)))
+                            .collect();
+
+                    (case.name, nodes, case.parents)
+                }
+            )
+            .collect()
+    }
+
+    fn generate_shapes() -> IndexedNodes<Shape>
+    {
+        let shapes: Vec<Shape> = vec! [
+            Circle::new(Point { x: 5., y: 5. }, 2.).into(),
+            Rectangle::square(Point::default(), 10.).polygon().into(),
+            Rectangle::square(Point { x: 4.5, y: 4.5 }, 1.).polygon().into(),
+            Circle::new(Point { x: 20., y: 20. }, 1.).into()
+        ];
+
+        shapes
+            .into_iter()
+            .enumerate()
+            .map(|(i, shape)| Node::new((i as isize, shape)))
+            .collect()
+    }
+
+    // The second polygon has no frame.
+
+    #[cfg(any(feature = "frames", feature = "quadtree"))]
+    fn generate_degenerate() -> IndexedNodes<Any>
+    {
+        let polygons = vec! [
+            Rectangle::square(Point::default(), 10.).polygon(),
+            Any { points: vec! [Point { x: 5., y: 5. }; 3] },
+            Rectangle::square(Point { x: 4.5, y: 4.5 }, 1.).polygon()
+        ];
+
+        polygons
+            .into_iter()
+            .enumerate()
+            .map(|(i, polygon)| Node::new((i as isize, polygon)))
+            .collect()
+    }
+
+    fn parents<P>(arena: &IndexedArena<P>) -> Vec<isize>
+    {
+        (1..arena.len())
+            .map(|id| arena.value(arena.parent(id).unwrap()).0)
+            .collect()
+    }
+
+    #[cfg(feature = "frames")]
+    #[test]
+    fn test_process_frames_adversarial()
+    {
+        for (name, nodes, expected) in generate_cases() {
+            assert_eq!(parents(&process_frames(&nodes)), expected, "{}", name);
+        }
+    }
+
+    #[cfg(feature = "naive")]
+    #[test]
+    fn test_process_naive_adversarial()
+    {
+        for (name, nodes, expected) in generate_cases() {
+            assert_eq!(parents(&process_naive(&nodes)), expected, "{}", name);
+        }
+    }
+
+    #[cfg(feature = "quadtree")]
+    #[test]
+    fn test_process_quadtree_adversarial()
+    {
+        for (name, nodes, expected) in generate_cases() {
+            let testing = process_quadtree(&nodes);
+
+            assert_eq!(parents(&testing), expected, "{}", name);
+        }
+    }
+
+    #[cfg(all(feature = "frames", feature = "quadtree"))]
+    #[test]
+    fn test_process_quadtree_against_frames()
+    {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let families = [
+            Family::Convex,
+            Family::Orthogonal,
+            Family::Spiral,
+            Family::Star
+        ];
+
+        for (seed, family) in families.into_iter().enumerate() {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+
+            let parameters = Parameters {
+                attempts: 1000,
+                corner_count: Ord::max(8, family.min_corner_count()),
+                dimension: 1000.,
+                family,
+                nesting: Nesting { branching: 2, depth: 2, fill: 0.6 },
+                polygon_count: 60,
+                radius: 40.
+            };
+
+            let nodes =
+                generate(&parameters, &mut rng)
+                    .unwrap()
+                    .polygons
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, polygon)| Node::new((i as isize, polygon)))
+                    .collect::<IndexedNodes>();
+
+            let (testing, stats) = process_quadtree_with_stats(&nodes);
+            let expected = process_frames(&nodes);
+
+            assert_eq!(parents(&testing), parents(&expected), "{:?}", family);
+            assert!(stats.filter_calls < nodes.len() * nodes.len());
+        }
+    }
+
+    #[cfg(feature = "frames")]
+    #[test]
+    fn test_process_frames_stats()
+    {
+        let (_, testing) = process_frames_with_stats(&generate_shapes());
+
+        assert_eq!(testing.queue_pops, 7);
+        assert_eq!(testing.reparents, 3);
+        assert_eq!(
+            testing.frame_rejections + testing.containment_tests,
+            testing.filter_calls
+        );
+        assert!(testing.frame_rejections > 0);
+    }
+
+    #[cfg(feature = "naive")]
+    #[test]
+    fn test_process_naive_stats()
+    {
+        let (_, testing) = process_naive_with_stats(&generate_shapes());
+
+        assert_eq!(testing.queue_pops, 7);
+        assert_eq!(testing.reparents, 3);
+        assert_eq!(testing.frame_rejections, 0);
+        assert_eq!(testing.containment_tests, testing.filter_calls);
+    }
+
+    #[cfg(feature = "frames")]
+    #[test]
+    fn test_process_frames_shapes()
+    {
+        let testing = process_frames(&generate_shapes());
+
+        assert_eq!(parents(&testing), vec! [1, -1, 0, -1]);
+    }
+
+    #[cfg(feature = "naive")]
+    #[test]
+    fn test_process_naive_shapes()
+    {
+        let testing = process_naive(&generate_shapes());
+
+        assert_eq!(parents(&testing), vec! [1, -1, 0, -1]);
+    }
+
+    #[cfg(feature = "quadtree")]
+    #[test]
+    fn test_process_quadtree_shapes()
+    {
+        let testing = process_quadtree(&generate_shapes());
+
+        assert_eq!(parents(&testing), vec! [1, -1, 0, -1]);
+    }
+
+    #[cfg(feature = "frames")]
+    #[test]
+    fn test_process_frames_degenerate()
+    {
+        let testing = process_frames(&generate_degenerate());
+
+        assert_eq!(parents(&testing), vec! [-1, -1, 0]);
+    }
+
+    #[cfg(feature = "quadtree")]
+    #[test]
+    fn test_process_quadtree_degenerate()
+    {
+        let testing = process_quadtree(&generate_degenerate());
+
+        assert_eq!(parents(&testing), vec! [-1, -1, 0]);
+    }
 }
@@ -1,15 +1,53 @@
+mod arc;
+pub use arc::Arc;
+
+mod edge;
+pub use edge::Edge;
+
 mod point;
 pub use point::Point;
 
 pub mod polygon;
 pub use polygon::Polygon;
 
+pub mod quadtree;
+pub use quadtree::Quadtree;
+
 mod segment;
 pub use segment::Segment;
 
 mod vector;
 pub use vector::Vector;
 
+use derive_more::Display;
+
+use std::error::Error;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Copy, Clone)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+pub enum GeometryError
+{
+    #[display(fmt = "degenerate arc: its start is its center")]
+    DegenerateArc,
+
+    #[display(fmt = "degenerate circle: its radius is not positive")]
+    DegenerateCircle,
+
+    #[display(fmt = "degenerate rectangle: a side is empty or crossed")]
+    DegenerateRectangle,
+
+    #[display(fmt = "degenerate segment: its ends are equal")]
+    DegenerateSegment,
+
+    #[display(fmt = "empty polygon: it has no corner")]
+    EmptyPolygon
+}
+
 /*********/
 /* TYPES */
 /*********/
@@ -42,6 +80,12 @@ pub trait Intersecter<Other = Self>
 
 pub trait SVG { fn to_svg(&self) -> String; }
 
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Error for GeometryError {}
+
 /*************/
 /* FUNCTIONS */
 /*************/
@@ -0,0 +1,528 @@
+use super::{Container, Distance, GeometryError, Intersecter, SVG};
+use super::{Point, Segment, Unit, Vector};
+
+use super::polygon::{frame_of, Rectangle};
+
+use derive_more::Display;
+use symm_impl::symmetric;
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const ANGLE_EPSILON: Unit = 1e-12;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Copy, Clone)]
+#[derive(Debug, Default, PartialEq)]
+#[derive(Display)]
+#[display(fmt = "({} ; {} -> {})", center, start, stop)]
+pub struct Arc { center: Point, start: Point, stop: Point, clockwise: bool }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Arc {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(center: Point, start: Point, stop: Point, clockwise: bool)
+        -> Self
+    {
+        Self::try_new(center, start, stop, clockwise).unwrap()
+    }
+
+    // Only the start gives the radius, the stop gives no more than an angle.
+
+    pub fn try_new(center: Point, start: Point, stop: Point, clockwise: bool)
+        -> Result<Self, GeometryError>
+    {
+        if center == start {
+            Err(GeometryError::DegenerateArc)
+        } else {
+            Ok(Self { center, start, stop, clockwise })
+        }
+    }
+
+    pub fn circle(center: Point, radius: Unit) -> Self
+    {
+        let start = Point { x: center.x + radius, y: center.y };
+
+        Self::new(center, start, start, false)
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn center(&self) -> &Point { &self.center }
+    pub fn start(&self) -> &Point { &self.start }
+    pub fn stop(&self) -> &Point { &self.stop }
+
+    pub fn is_clockwise(&self) -> bool { self.clockwise }
+    pub fn is_full(&self) -> bool { self.start == self.stop }
+
+    pub fn length(&self) -> Unit { self.radius() * self.sweep().abs() }
+    pub fn radius(&self) -> Unit { self.center.distance_from(&self.start) }
+    pub fn start_angle(&self) -> Unit { angle_of(&self.center, &self.start) }
+
+    pub fn sweep(&self) -> Unit
+    {
+        let sweep =
+            if self.is_full() {
+                TAU
+            } else {
+                let stop_angle = angle_of(&self.center, &self.stop);
+
+                if self.clockwise {
+                    (self.start_angle() - stop_angle).rem_euclid(TAU)
+                } else {
+                    (stop_angle - self.start_angle()).rem_euclid(TAU)
+                }
+            };
+
+        if self.clockwise { -sweep } else { sweep }
+    }
+
+    pub fn covers(&self, angle: Unit) -> bool
+    {
+        let offset = self.offset_of(angle);
+
+        (offset <= self.sweep().abs() + ANGLE_EPSILON) ||
+            (offset >= TAU - ANGLE_EPSILON)
+    }
+
+    pub fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        let extrema =
+            [0., FRAC_PI_2, PI, -FRAC_PI_2]
+                .into_iter()
+                .filter(|&angle| self.covers(angle))
+                .map(|angle| self.point_at(angle));
+
+        frame_of([self.start, self.stop].into_iter().chain(extrema))
+    }
+
+    pub fn point_at(&self, angle: Unit) -> Point
+    {
+        let r = self.radius();
+
+        Point {
+            x: self.center.x + r * angle.cos(),
+            y: self.center.y + r * angle.sin()
+        }
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn flatten(&self, tolerance: Unit) -> Vec<Point>
+    {
+        assert!(tolerance > 0.);
+
+        let r = self.radius();
+        let sweep = self.sweep();
+
+        let step = 2. * (1. - (tolerance / r).min(1.)).acos();
+        let minimum = (sweep.abs() * 3. / TAU).ceil();
+        let count = (sweep.abs() / step).ceil().max(minimum).max(1.) as usize;
+
+        let a = self.start_angle();
+
+        (0..count)
+            .map(
+                |i| {
+                    if i == 0 {
+                        self.start
+                    } else {
+                        self.point_at(a + sweep * (i as Unit) / (count as Unit))
+                    }
+                }
+            )
+            .collect()
+    }
+
+    /*********/
+    /* UTILS */
+    /*********/
+
+    pub(crate) fn crossings(&self, point: &Point) -> usize
+    {
+        self
+            .monotone_pieces()
+            .into_iter()
+            .filter(|(a, b, _)| (a.y > point.y) != (b.y > point.y))
+            .filter(
+                |(_, _, is_right)| {
+                    let r = self.radius();
+                    let dy = point.y - self.center.y;
+                    let dx = (r.powi(2) - dy.powi(2)).max(0.).sqrt();
+
+                    let x =
+                        if *is_right { self.center.x + dx }
+                        else { self.center.x - dx };
+
+                    x > point.x
+                }
+            )
+            .count()
+    }
+
+    pub(crate) fn wedge(&self) -> Unit
+    {
+        let chord = Vector::from((self.start, self.stop));
+
+        Vector::from(self.center).det(&chord) +
+            self.radius().powi(2) * self.sweep()
+    }
+
+    fn covers_point(&self, point: &Point) -> bool
+    {
+        self.covers(angle_of(&self.center, point))
+    }
+
+    pub(crate) fn svg_command(&self) -> String
+    {
+        let r = self.radius();
+        let sweep_flag = if self.clockwise { 0 } else { 1 };
+
+        if self.is_full() {
+            let opposite =
+                Point {
+                    x: 2. * self.center.x - self.start.x,
+                    y: 2. * self.center.y - self.start.y
+                };
+
+            let half = format!("A {r} {r} 0 0 {sweep_flag}");
+
+            format!(
+                "{half} {} {} {half} {} {}",
+                opposite.x,
+                opposite.y,
+                self.stop.x,
+                self.stop.y
+            )
+        } else {
+            let large_arc_flag = if self.sweep().abs() > PI { 1 } else { 0 };
+
+            format!(
+                "A {r} {r} 0 {large_arc_flag} {sweep_flag} {} {}",
+                self.stop.x,
+                self.stop.y
+            )
+        }
+    }
+
+    fn monotone_pieces(&self) -> Vec<(Point, Point, bool)>
+    {
+        let r = self.radius();
+        let sweep = self.sweep();
+        let top = Point { x: self.center.x, y: self.center.y + r };
+        let bottom = Point { x: self.center.x, y: self.center.y - r };
+
+        let mut splits =
+            [(FRAC_PI_2, top), (-FRAC_PI_2, bottom)]
+                .into_iter()
+                .map(|(angle, point)| (self.offset_of(angle), point))
+                .filter(|&(offset, _)| offset > 0. && offset < sweep.abs())
+                .collect::<Vec<_>>();
+
+        splits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let offsets =
+            std::iter::once(0.)
+                .chain(splits.iter().map(|&(offset, _)| offset))
+                .chain(std::iter::once(sweep.abs()))
+                .collect::<Vec<_>>();
+
+        let points =
+            std::iter::once(self.start)
+                .chain(splits.iter().map(|&(_, point)| point))
+                .chain(std::iter::once(self.stop))
+                .collect::<Vec<_>>();
+
+        let a = self.start_angle();
+        let direction = sweep.signum();
+
+        (0..(points.len() - 1))
+            .map(
+                |i| {
+                    let middle = (offsets[i] + offsets[i + 1]) / 2.;
+                    let is_right = (a + direction * middle).cos() > 0.;
+
+                    (points[i], points[i + 1], is_right)
+                }
+            )
+            .collect()
+    }
+
+    fn offset_of(&self, angle: Unit) -> Unit
+    {
+        if self.clockwise {
+            (self.start_angle() - angle).rem_euclid(TAU)
+        } else {
+            (angle - self.start_angle()).rem_euclid(TAU)
+        }
+    }
+
+    fn radius_epsilon(&self) -> Unit { Unit::EPSILON * self.radius().max(1.) }
+}
+
+/***************/
+/* `Container` */
+/***************/
+
+impl Container<Point> for Arc {
+    fn contains(&self, point: &Point) -> bool
+    {
+        let distance = self.center.distance_from(point);
+
+        ((distance - self.radius()).abs() < self.radius_epsilon()) &&
+            self.covers(angle_of(&self.center, point))
+    }
+}
+
+/*****************/
+/* `Intersecter` */
+/*****************/
+
+impl Intersecter for Arc {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        if share_extremity((self.start, self.stop), (other.start, other.stop)) {
+            return true;
+        }
+
+        let (r1, r2) = (self.radius(), other.radius());
+        let u = Vector::from((self.center, other.center));
+        let d = u.norm();
+
+        if d < Unit::EPSILON {
+            ((r1 - r2).abs() < self.radius_epsilon()) &&
+                (self.contains(&other.start) || other.contains(&self.start))
+        } else if (d > r1 + r2) || (d < (r1 - r2).abs()) {
+            false
+        } else {
+            let a = (r1.powi(2) - r2.powi(2) + d.powi(2)) / (2. * d);
+            let h = (r1.powi(2) - a.powi(2)).max(0.).sqrt();
+            let base = Vector::from(self.center) + (a / d) * u;
+            let offset = (h / d) * u.orthogonal();
+
+            [base + offset, base - offset]
+                .into_iter()
+                .map(Point::from)
+                .any(|p| self.covers_point(&p) && other.covers_point(&p))
+        }
+    }
+}
+
+#[symmetric]
+impl Intersecter<Segment> for Arc {
+    fn intersects(&self, other: &Segment) -> bool
+    {
+        let (start, stop) = (*other).into();
+
+        if share_extremity((self.start, self.stop), (start, stop)) {
+            return true;
+        }
+
+        let d = Vector::from((start, stop));
+        let f = Vector::from((self.center, start));
+
+        let a = d.squared_norm();
+        let b = 2. * f.dot(&d);
+        let c = f.squared_norm() - self.radius().powi(2);
+        let discriminant = b.powi(2) - 4. * a * c;
+
+        if discriminant < 0. {
+            false
+        } else {
+            let root = discriminant.sqrt();
+
+            [(-b - root) / (2. * a), (-b + root) / (2. * a)]
+                .into_iter()
+                .filter(|t| (-Unit::EPSILON..=(1. + Unit::EPSILON)).contains(t))
+                .map(|t| Point::from(Vector::from(start) + t * d))
+                .any(|p| self.covers_point(&p))
+        }
+    }
+}
+
+/*********/
+/* `SVG` */
+/*********/
+
+impl SVG for Arc {
+    fn to_svg(&self) -> String
+    {
+        format!(
+            r#"<path d="M {} {} {}" />"#,
+            self.start.x,
+            self.start.y,
+            self.svg_command()
+        )
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+fn angle_of(center: &Point, point: &Point) -> Unit
+{
+    (point.y - center.y).atan2(point.x - center.x)
+}
+
+fn share_extremity((a, b): (Point, Point), (c, d): (Point, Point)) -> bool
+{
+    (a == c) || (a == d) || (b == c) || (b == d)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn half_circle() -> Arc
+    {
+        Arc::new(
+            Point::default(),
+            Point { x: 1., y: 0. },
+            Point { x: -1., y: 0. },
+            false
+        )
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    #[test]
+    fn test_try_new()
+    {
+        let (a, b) = (Point::default(), Point { x: 1., y: 0. });
+
+        assert_eq!(
+            Arc::try_new(a, b, b, true),
+            Ok(Arc::new(a, b, b, true))
+        );
+
+        assert_eq!(
+            Arc::try_new(a, a, b, false),
+            Err(GeometryError::DegenerateArc)
+        );
+    }
+
+    #[test]
+    fn test_sweep()
+    {
+        let circle = Arc::circle(Point::default(), 1.);
+
+        assert!((half_circle().sweep() - PI).abs() < ANGLE_EPSILON);
+        assert!((circle.sweep() - TAU).abs() < ANGLE_EPSILON);
+    }
+
+    #[test]
+    fn test_sweep_clockwise()
+    {
+        let testing =
+            Arc::new(
+                Point::default(),
+                Point { x: 1., y: 0. },
+                Point { x: 0., y: 1. },
+                true
+            );
+
+        assert!((testing.sweep() + 3. * FRAC_PI_2).abs() < ANGLE_EPSILON);
+    }
+
+    #[test]
+    fn test_frame()
+    {
+        let expected =
+            Rectangle::new(Point { x: -1., y: 0. }, Point { x: 1., y: 1. });
+
+        assert_eq!(half_circle().frame(), Ok(expected));
+    }
+
+    #[test]
+    fn test_length()
+    {
+        assert!((half_circle().length() - PI).abs() < ANGLE_EPSILON);
+    }
+
+    /***************/
+    /* `Container` */
+    /***************/
+
+    #[test]
+    fn test_contains()
+    {
+        assert!(half_circle().contains(&Point { x: 0., y: 1. }));
+        assert!(!half_circle().contains(&Point { x: 0., y: -1. }));
+        assert!(!half_circle().contains(&Point::default()));
+    }
+
+    /*****************/
+    /* `Intersecter` */
+    /*****************/
+
+    #[test]
+    fn test_intersects_arc()
+    {
+        let other =
+            Arc::new(
+                Point { x: 0., y: 1. },
+                Point { x: -1., y: 1. },
+                Point { x: 1., y: 1. },
+                false
+            );
+
+        assert!(half_circle().intersects(&other));
+    }
+
+    #[test]
+    fn test_intersects_arc_uncovered()
+    {
+        let other =
+            Arc::new(
+                Point { x: 0., y: 1. },
+                Point { x: 1., y: 1. },
+                Point { x: -1., y: 1. },
+                false
+            );
+
+        assert!(!half_circle().intersects(&other));
+    }
+
+    #[test]
+    fn test_intersects_segment()
+    {
+        let a = Segment::new(Point { x: 0., y: 0.5 }, Point { x: 0., y: 2. });
+        let b = Segment::new(Point { x: 0., y: -0.5 }, Point { x: 0., y: -2. });
+
+        assert!(half_circle().intersects(&a));
+        assert!(a.intersects(&half_circle()));
+        assert!(!half_circle().intersects(&b));
+    }
+
+    /*********/
+    /* `SVG` */
+    /*********/
+
+    #[test]
+    fn test_to_svg()
+    {
+        let expected = r#"<path d="M 1 0 A 1 1 0 0 1 -1 0" />"#;
+
+        assert_eq!(half_circle().to_svg(), expected);
+    }
+}
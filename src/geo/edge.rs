@@ -0,0 +1,148 @@
+use super::{Arc, Point, Segment, Unit, Vector};
+use super::{Container, GeometryError, Intersecter};
+
+use super::polygon::{frame_of, Rectangle};
+
+use derive_more::From;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Copy, Clone)]
+#[derive(Debug, From, PartialEq)]
+pub enum Edge { Arc(Arc), Line(Segment) }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Edge {
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        match self {
+            Edge::Arc(arc) => arc.frame(),
+            Edge::Line(line) => {
+                let (start, stop) = (*line).into();
+
+                frame_of([start, stop].into_iter())
+            }
+        }
+    }
+
+    pub fn start(&self) -> &Point
+    {
+        match self {
+            Edge::Arc(arc) => arc.start(),
+            Edge::Line(line) => line.start()
+        }
+    }
+
+    pub fn stop(&self) -> &Point
+    {
+        match self {
+            Edge::Arc(arc) => arc.stop(),
+            Edge::Line(line) => line.stop()
+        }
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn flatten(&self, tolerance: Unit) -> Vec<Point>
+    {
+        match self {
+            Edge::Arc(arc) => arc.flatten(tolerance),
+            Edge::Line(line) => vec! [*line.start()]
+        }
+    }
+
+    /*********/
+    /* UTILS */
+    /*********/
+
+    pub(crate) fn crossings(&self, point: &Point) -> usize
+    {
+        match self {
+            Edge::Arc(arc) => arc.crossings(point),
+
+            Edge::Line(line) => {
+                let (a, b) = (*line).into();
+
+                if (a.y > point.y) != (b.y > point.y) {
+                    let x = a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y);
+
+                    usize::from(x > point.x)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    pub(crate) fn wedge(&self) -> Unit
+    {
+        match self {
+            Edge::Arc(arc) => arc.wedge(),
+
+            Edge::Line(line) => {
+                Vector::from(*line.start()).det(&(*line.stop()).into())
+            }
+        }
+    }
+}
+
+impl Container<Point> for Edge {
+    fn contains(&self, point: &Point) -> bool
+    {
+        match self {
+            Edge::Arc(arc) => arc.contains(point),
+            Edge::Line(line) => line.contains(point)
+        }
+    }
+}
+
+impl Intersecter for Edge {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        match (self, other) {
+            (Edge::Arc(a), Edge::Arc(b)) => a.intersects(b),
+            (Edge::Arc(a), Edge::Line(b)) => a.intersects(b),
+            (Edge::Line(a), Edge::Arc(b)) => a.intersects(b),
+            (Edge::Line(a), Edge::Line(b)) => a.intersects(b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_crossings_line()
+    {
+        let testing =
+            Edge::from(
+                Segment::new(Point { x: 1., y: -1. }, Point { x: 1., y: 1. })
+            );
+
+        assert_eq!(testing.crossings(&Point::default()), 1);
+        assert_eq!(testing.crossings(&Point { x: 2., y: 0. }), 0);
+    }
+
+    #[test]
+    fn test_crossings_circle()
+    {
+        let testing: Edge = Arc::circle(Point::default(), 1.).into();
+
+        assert_eq!(testing.crossings(&Point::default()), 1);
+        assert_eq!(testing.crossings(&Point { x: -2., y: 0.5 }), 2);
+        assert_eq!(testing.crossings(&Point { x: 0., y: 2. }), 0);
+    }
+}
@@ -1,4 +1,4 @@
-use super::SVG;
+use super::{Container, GeometryError, SVG};
 use super::{Point, Unit};
 
 /***********/
@@ -8,9 +8,24 @@ use super::{Point, Unit};
 mod any;
 pub use any::Any;
 
+mod circle;
+pub use circle::Circle;
+
+mod curved;
+pub use curved::Curved;
+
+mod prepared;
+pub use prepared::{PreparedAny, PREPARED_MIN_LEN};
+
+mod repair;
+pub use repair::{Fix, Repair};
+
 mod rectangle;
 pub use rectangle::Rectangle;
 
+mod shape;
+pub use shape::Shape;
+
 /**********/
 /* TRAITS */
 /**********/
@@ -19,17 +34,35 @@ pub trait Polygon: SVG
 {
     fn area(&self) -> Unit;
     fn len(&self) -> usize;
+    fn is_empty(&self) -> bool { self.len() == 0 }
     fn is_valid(&self) -> bool;
-    fn frame(&self) -> Rectangle;
+
+    // Fails like `frame_of` for a polygon without extent.
+
+    fn frame(&self) -> Result<Rectangle, GeometryError>;
+}
+
+// The form a polygon takes while a hierarchy is built, which may answer the
+// containment tests faster, and from which it is restored afterwards.
+
+pub trait Prepare: Sized
+{
+    type Prepared: Clone + Container + Default + Polygon;
+
+    fn prepare(self) -> Self::Prepared;
+    fn restore(prepared: Self::Prepared) -> Self;
 }
 
 /*************/
 /* FUNCTIONS */
 /*************/
 
-pub fn frame_of<T>(mut iter: T) -> Rectangle where T: Iterator<Item = Point>
+// Fails when there is no point, or when the points are all equal.
+
+pub fn frame_of<T>(mut iter: T) -> Result<Rectangle, GeometryError>
+    where T: Iterator<Item = Point>
 {
-    let first = iter.next().unwrap();
+    let first = iter.next().ok_or(GeometryError::EmptyPolygon)?;
 
     let (mut xmin, mut ymin) = first.into();
     let (mut xmax, mut ymax) = first.into();
@@ -50,5 +83,5 @@ pub fn frame_of<T>(mut iter: T) -> Rectangle where T: Iterator<Item = Point>
         }
     }
 
-    Rectangle::new((xmin, ymin).into(), (xmax, ymax).into())
+    Rectangle::try_new((xmin, ymin).into(), (xmax, ymax).into())
 }
@@ -1,11 +1,9 @@
-use super::super::{Container, Intersecter, SVG};
+use super::super::{Container, GeometryError, Intersecter, SVG};
 use super::super::{Point, Segment, Unit, Vector};
 
 use super::{Polygon, Rectangle};
 use super::frame_of;
 
-use std::iter::once;
-
 /**************/
 /* STRUCTURES */
 /**************/
@@ -22,13 +20,13 @@ impl Any {
     /* GETTERS */
     /***********/
 
-    pub fn segment(&self, index: usize) -> Segment
+    pub fn segment(&self, index: usize) -> Result<Segment, GeometryError>
     {
         let next =
             if index == (self.points.len() - 1) { 0 }
             else { index + 1 };
 
-        Segment::new(self.points[index], self.points[next])
+        Segment::try_new(self.points[index], self.points[next])
     }
 
     /*************/
@@ -40,7 +38,8 @@ impl Any {
         self.points.iter()
     }
 
-    pub fn segments(&self) -> impl Clone + Iterator<Item = Segment> + '_
+    pub fn segments(&self)
+        -> impl Clone + Iterator<Item = Result<Segment, GeometryError>> + '_
     {
         (0..self.len()).map(|index| self.segment(index))
     }
@@ -50,12 +49,6 @@ impl Any {
     {
         self.points().zip(self.points.iter().skip(1))
     }
-
-    pub fn pairs_of_segments(&self)
-        -> impl Clone + Iterator<Item = (Segment, Segment)> + '_
-    {
-        self.segments().zip(self.segments().skip(1))
-    }
 }
 
 /***************/
@@ -64,74 +57,51 @@ impl Any {
 
 impl Container for Any {
     fn contains(&self, other: &Self) -> bool
-        { self.contains(&other.points[0]) }
+    {
+        other.points.first().is_some_and(|point| self.contains(point))
+    }
 }
 
 impl Container<Point> for Any {
     fn contains(&self, &other: &Point) -> bool
     {
-        fn same_sign(a: Unit, b: Unit) -> bool
-        {
-            a.is_sign_negative() == b.is_sign_negative()
-        }
+        // A polygon without extent or with a degenerate segment has no inside.
+
+        let Ok(frame) = self.frame() else {
+            return false;
+        };
 
-        let y = self.frame().top_right().y;
+        let y = frame.top_right().y;
 
         if other.y >= y {
-            false
-        } else {
-            let last = (self.segment(self.len() - 1), self.segment(0));
-            let point = (other.x, y).into();
-            let u = Segment::new(other, point);
-
-            let count =
-                self
-                    .pairs_of_segments()
-                    .chain(once(last))
-                    .map(
-                        |(a, b)| {
-                            if a.intersects(&u) {
-                                if b.intersects(&u) {
-                                    let a: Vector = a.into();
-                                    let b: Vector = b.into();
-                                    let u: Vector = u.into();
-
-
-                                    if same_sign(u.det(&a), u.det(&b)) {
-                                        // Counting 0 instead of 1 because the
-                                        // intersection will be counted with
-                                        // the next segment pair.
-
-                                        0
-                                    } else {
-                                        // Counting 1 instead of 0 allowing the
-                                        // intersection to be counted twice,
-                                        // (once here, once with the next
-                                        // segment pair) which is the same as
-                                        // not being counted at all.
-
-                                        1
-                                    }
-                                } else {
-                                    1
-                                }
-                            } else {
-                                0
-                            }
-                        }
-                    )
-                    .sum::<usize>();
-
-            (count % 2) == 1
+            return false;
         }
+
+        let Ok(u) = Segment::try_new(other, (other.x, y).into()) else {
+            return false;
+        };
+
+        let mut count = 0;
+
+        for (a, b) in self.segments().zip(self.segments().cycle().skip(1)) {
+            let (Ok(a), Ok(b)) = (a, b) else {
+                return false;
+            };
+
+            count += crossings(a, b, &u);
+        }
+
+        (count % 2) == 1
     }
 }
 
 impl Intersecter for Any {
     fn intersects(&self, other: &Self) -> bool
     {
-        for a in self.segments() {
-            for b in other.segments() {
+        // Degenerate segments are points of their neighbours.
+
+        for a in self.segments().flatten() {
+            for b in other.segments().flatten() {
                 if a.intersects(&b) {
                     return true;
                 }
@@ -147,13 +117,19 @@ impl Polygon for Any {
 
     fn is_valid(&self) -> bool
     {
-        if self.points.len() > 2 {
-            let mut iter = self.segments();
+        // Two equal consecutive corners do not make a segment.
 
-            while let Some(i) = iter.next() {
-                let mut iter = iter.clone();
+        let is_repeated =
+            self
+                .points()
+                .zip(self.points().cycle().skip(1))
+                .any(|(a, b)| a == b);
 
-                while let Some(j) = iter.next() {
+        if self.points.len() > 2 && !is_repeated {
+            let mut iter = self.segments().flatten();
+
+            while let Some(i) = iter.next() {
+                for j in iter.clone() {
                     let common_extremity = {
                         let (a, b) = i.into();
                         let (c, d) = j.into();
@@ -176,13 +152,17 @@ impl Polygon for Any {
     fn area(&self) -> Unit
     {
         self
-            .pairs_of_points()
+            .points()
+            .zip(self.points().cycle().skip(1))
             .map(|(&x, &y)| Vector::from(x).det(&y.into()))
             .sum::<Unit>()
             .abs() / 2.
     }
 
-    fn frame(&self) -> Rectangle { frame_of(self.points().cloned()) }
+    fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        frame_of(self.points().cloned())
+    }
 }
 
 impl SVG for Any {
@@ -200,6 +180,46 @@ impl SVG for Any {
     }
 }
 
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// What the segment `a`, followed by `b`, adds to the count of crossings of the
+// ray `u` through the boundary.
+
+pub(super) fn crossings(a: Segment, b: Segment, u: &Segment) -> usize
+{
+    fn same_sign(a: Unit, b: Unit) -> bool
+    {
+        a.is_sign_negative() == b.is_sign_negative()
+    }
+
+    if a.intersects(u) {
+        if b.intersects(u) {
+            let a: Vector = a.into();
+            let b: Vector = b.into();
+            let u: Vector = (*u).into();
+
+            if same_sign(u.det(&a), u.det(&b)) {
+                // Counting 0 instead of 1 because the intersection will be
+                // counted with the next segment pair.
+
+                0
+            } else {
+                // Counting 1 instead of 0 allowing the intersection to be
+                // counted twice, (once here, once with the next segment pair)
+                // which is the same as not being counted at all.
+
+                1
+            }
+        } else {
+            1
+        }
+    } else {
+        0
+    }
+}
+
 #[cfg(test)]
 mod tests
 {
@@ -477,6 +497,21 @@ mod tests
         assert_eq!(testing.area(), 1.)
     }
 
+    #[test]
+    fn test_area_away_from_origin()
+    {
+        let testing =
+            Any {
+                points: vec! [
+                    Point { x: 1., y: 1. },
+                    Point { x: 2., y: 2. },
+                    Point { x: 2., y: 0. }
+                ]
+            };
+
+        assert_eq!(testing.area(), 1.)
+    }
+
     #[test]
     fn test_frame()
     {
@@ -495,7 +530,65 @@ mod tests
                 ]
             };
 
-        assert_eq!(testing.frame(), expected);
+        assert_eq!(testing.frame(), Ok(expected));
+    }
+
+    #[test]
+    fn test_frame_errors()
+    {
+        let testing = Any { points: vec! [Point::default(); 3] };
+
+        let empty = Any::default();
+
+        assert_eq!(
+            testing.frame(),
+            Err(GeometryError::DegenerateRectangle)
+        );
+        assert_eq!(empty.frame(), Err(GeometryError::EmptyPolygon));
+    }
+
+    #[test]
+    fn test_segments_errors()
+    {
+        let testing =
+            Any {
+                points: vec! [
+                    Point::default(),
+                    Point { x: 1e-20, y: 0. },
+                    Point { x: 0., y: 1. }
+                ]
+            };
+
+        let errors =
+            testing
+                .segments()
+                .map(|segment| segment.err())
+                .collect::<Vec<_>>();
+
+        let expected =
+            vec! [Some(GeometryError::DegenerateSegment), None, None];
+
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn test_contains_degenerate()
+    {
+        let point = Point { x: 0.5, y: 0.1 };
+
+        let testing =
+            Any {
+                points: vec! [
+                    Point::default(),
+                    Point { x: 1e-20, y: 0. },
+                    Point { x: 1., y: 0. },
+                    Point { x: 0., y: 1. }
+                ]
+            };
+
+        assert!(!testing.contains(&point));
+        assert!(!Any::default().contains(&point));
+        assert!(!testing.contains(&Any::default()));
     }
 
     /*********/
@@ -0,0 +1,211 @@
+use super::{Any, Polygon, Rectangle};
+
+use super::super::{Container, Distance, GeometryError, Intersecter, SVG};
+use super::super::{Arc, Edge, Point, Unit};
+
+use std::f64::consts::PI;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Default, PartialEq)]
+pub struct Circle { center: Point, radius: Unit }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Circle {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(center: Point, radius: Unit) -> Self
+    {
+        Self::try_new(center, radius).unwrap()
+    }
+
+    pub fn try_new(center: Point, radius: Unit) -> Result<Self, GeometryError>
+    {
+        if radius.is_finite() && (radius > 0.) {
+            Ok(Self { center, radius })
+        } else {
+            Err(GeometryError::DegenerateCircle)
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn center(&self) -> &Point { &self.center }
+    pub fn radius(&self) -> Unit { self.radius }
+
+    pub fn edge(&self) -> Edge { Arc::circle(self.center, self.radius).into() }
+
+    pub fn flatten(&self, tolerance: Unit) -> Any
+    {
+        Any { points: Arc::circle(self.center, self.radius).flatten(tolerance) }
+    }
+}
+
+/***************/
+/* `Container` */
+/***************/
+
+impl Container for Circle {
+    fn contains(&self, other: &Self) -> bool
+    {
+        self.center.distance_from(&other.center) + other.radius < self.radius
+    }
+}
+
+impl Container<Point> for Circle {
+    fn contains(&self, other: &Point) -> bool
+    {
+        self.center.distance_from(other) < self.radius
+    }
+}
+
+impl Intersecter for Circle {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        let distance = self.center.distance_from(&other.center);
+
+        (distance <= self.radius + other.radius) &&
+            (distance >= (self.radius - other.radius).abs())
+    }
+}
+
+impl Polygon for Circle {
+    fn len(&self) -> usize { 1 }
+    fn area(&self) -> Unit { PI * self.radius.powi(2) }
+    fn is_valid(&self) -> bool { self.radius.is_finite() && self.radius > 0. }
+
+    fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        let (x, y) = self.center.into();
+
+        Rectangle::try_new(
+            Point { x: x - self.radius, y: y - self.radius },
+            Point { x: x + self.radius, y: y + self.radius }
+        )
+    }
+}
+
+impl SVG for Circle {
+    fn to_svg(&self) -> String
+    {
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}" />"#,
+            self.center.x,
+            self.center.y,
+            self.radius
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    #[test]
+    fn test_try_new()
+    {
+        let center = Point { x: 1., y: 2. };
+
+        assert_eq!(Circle::try_new(center, 3.), Ok(Circle::new(center, 3.)));
+
+        for radius in [0., -1., Unit::NAN, Unit::INFINITY] {
+            assert_eq!(
+                Circle::try_new(center, radius),
+                Err(GeometryError::DegenerateCircle)
+            );
+        }
+    }
+
+    /***************/
+    /* `Container` */
+    /***************/
+
+    #[test]
+    fn test_contains()
+    {
+        let a = Circle::new(Point::default(), 3.);
+        let b = Circle::new(Point { x: 1., y: 1. }, 1.);
+
+        assert!(a.contains(&b));
+        assert!(!b.contains(&a));
+        assert!(a.contains(&Point { x: 2., y: 2. }));
+        assert!(!a.contains(&Point { x: 3., y: 0. }));
+    }
+
+    /*****************/
+    /* `Intersecter` */
+    /*****************/
+
+    #[test]
+    fn test_intersects()
+    {
+        let a = Circle::new(Point::default(), 2.);
+        let b = Circle::new(Point { x: 3., y: 0. }, 2.);
+        let c = Circle::new(Point::default(), 1.);
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+    }
+
+    /*************/
+    /* `Polygon` */
+    /*************/
+
+    #[test]
+    fn test_flatten()
+    {
+        let testing = Circle::new(Point::default(), 1.);
+        let flattened = testing.flatten(0.01);
+
+        assert!(flattened.is_valid());
+        assert!(flattened.len() > 3);
+
+        for p in flattened.points() {
+            assert!((p.distance_from(&Point::default()) - 1.).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_frame()
+    {
+        let expected =
+            Rectangle::new(Point { x: 0., y: -1. }, Point { x: 2., y: 1. });
+
+        let testing = Circle::new(Point { x: 1., y: 0. }, 1.);
+
+        assert_eq!(testing.frame(), Ok(expected));
+
+        assert_eq!(
+            Circle::default().frame(),
+            Err(GeometryError::DegenerateRectangle)
+        );
+    }
+
+    /*********/
+    /* `SVG` */
+    /*********/
+
+    #[test]
+    fn test_to_svg()
+    {
+        let expected = r#"<circle cx="1" cy="2" r="3" />"#;
+        let testing = Circle::new(Point { x: 1., y: 2. }, 3.);
+
+        assert_eq!(testing.to_svg(), expected);
+    }
+}
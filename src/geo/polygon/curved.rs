@@ -0,0 +1,278 @@
+use super::{Any, Polygon, Rectangle};
+
+use super::super::{Container, GeometryError, Intersecter, SVG};
+use super::super::{Edge, Point, Unit};
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Curved { pub edges: Vec<Edge> }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Curved {
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn flatten(&self, tolerance: Unit) -> Any
+    {
+        let points =
+            self
+                .edges
+                .iter()
+                .flat_map(|edge| edge.flatten(tolerance))
+                .collect();
+
+        Any { points }
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn edges(&self) -> impl Clone + Iterator<Item = &Edge>
+    {
+        self.edges.iter()
+    }
+}
+
+/***************/
+/* `Container` */
+/***************/
+
+impl Container for Curved {
+    fn contains(&self, other: &Self) -> bool
+    {
+        other.edges.first().is_some_and(|edge| self.contains(edge.start()))
+    }
+}
+
+impl Container<Point> for Curved {
+    fn contains(&self, other: &Point) -> bool
+    {
+        if self.edges().any(|edge| edge.contains(other)) {
+            false
+        } else {
+            let count =
+                self
+                    .edges()
+                    .map(|edge| edge.crossings(other))
+                    .sum::<usize>();
+
+            (count % 2) == 1
+        }
+    }
+}
+
+impl Intersecter for Curved {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        self.edges().any(|a| other.edges().any(|b| a.intersects(b)))
+    }
+}
+
+impl Polygon for Curved {
+    fn len(&self) -> usize { self.edges.len() }
+
+    fn is_valid(&self) -> bool
+    {
+        if self.edges.is_empty() {
+            false
+        } else {
+            let is_closed =
+                self
+                    .edges()
+                    .zip(self.edges().cycle().skip(1))
+                    .all(|(a, b)| a.stop() == b.start());
+
+            let mut iter = self.edges();
+
+            while let Some(i) = iter.next() {
+                for j in iter.clone() {
+                    let common_extremity =
+                        (i.start() == j.start()) || (i.start() == j.stop()) ||
+                        (i.stop() == j.start()) || (i.stop() == j.stop());
+
+                    if !common_extremity && i.intersects(j) {
+                        return false;
+                    }
+                }
+            }
+
+            is_closed
+        }
+    }
+
+    fn area(&self) -> Unit
+    {
+        self.edges().map(Edge::wedge).sum::<Unit>().abs() / 2.
+    }
+
+    fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        let mut frames = self.edges().map(Edge::frame);
+        let first = frames.next().ok_or(GeometryError::EmptyPolygon)??;
+
+        frames.try_fold(first, |a, b| Ok(a.union(&b?)))
+    }
+}
+
+impl SVG for Curved {
+    fn to_svg(&self) -> String
+    {
+        let mut path = match self.edges.first() {
+            Some(edge) => format!("M {} {}", edge.start().x, edge.start().y),
+            None => String::new()
+        };
+
+        for edge in self.edges() {
+            match edge {
+                Edge::Arc(arc) => path += &format!(" {}", arc.svg_command()),
+                Edge::Line(line) => {
+                    path += &format!(" L {} {}", line.stop().x, line.stop().y)
+                }
+            }
+        }
+
+        format!(r#"<path d="{} Z" />"#, path)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::super::{Arc, Segment};
+
+    use std::f64::consts::PI;
+
+    fn half_disk() -> Curved
+    {
+        let a = Point { x: 1., y: 0. };
+        let b = Point { x: -1., y: 0. };
+
+        Curved {
+            edges: vec! [
+                Arc::new(Point::default(), a, b, false).into(),
+                Segment::new(b, a).into()
+            ]
+        }
+    }
+
+    /***************/
+    /* `Container` */
+    /***************/
+
+    #[test]
+    fn test_contains()
+    {
+        let testing = half_disk();
+
+        assert!(testing.contains(&Point { x: 0., y: 0.5 }));
+        assert!(testing.contains(&Point { x: 0.5, y: 0.5 }));
+        assert!(!testing.contains(&Point { x: 0., y: -0.5 }));
+        assert!(!testing.contains(&Point { x: 0.9, y: 0.9 }));
+    }
+
+    #[test]
+    fn test_contains_on_boundary()
+    {
+        let testing = half_disk();
+
+        assert!(!testing.contains(&Point::default()));
+        assert!(!testing.contains(&Point { x: 0., y: 1. }));
+    }
+
+    #[test]
+    fn test_contains_empty()
+    {
+        let testing = half_disk();
+
+        assert!(!Curved::default().contains(&Point::default()));
+        assert!(!Curved::default().contains(&testing));
+        assert!(!testing.contains(&Curved::default()));
+    }
+
+    /*****************/
+    /* `Intersecter` */
+    /*****************/
+
+    #[test]
+    fn test_intersects()
+    {
+        let a = half_disk();
+
+        let p = Point { x: 0., y: 0.5 };
+        let q = Point { x: 0., y: 2. };
+        let r = Point { x: 2., y: 2. };
+
+        let b =
+            Curved {
+                edges: vec! [
+                    Segment::new(p, q).into(),
+                    Segment::new(q, r).into(),
+                    Segment::new(r, p).into()
+                ]
+            };
+
+        assert!(a.intersects(&b));
+    }
+
+    /*************/
+    /* `Polygon` */
+    /*************/
+
+    #[test]
+    fn test_area()
+    {
+        assert!((half_disk().area() - PI / 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_flatten()
+    {
+        let testing = half_disk().flatten(0.01);
+
+        assert!(testing.is_valid());
+        assert!((testing.area() - PI / 2.).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_frame()
+    {
+        let expected =
+            Rectangle::new(Point { x: -1., y: 0. }, Point { x: 1., y: 1. });
+
+        assert_eq!(half_disk().frame(), Ok(expected));
+    }
+
+    #[test]
+    fn test_is_valid()
+    {
+        let mut testing = half_disk();
+
+        assert!(testing.is_valid());
+
+        testing.edges.pop();
+
+        assert!(!testing.is_valid());
+    }
+
+    /*********/
+    /* `SVG` */
+    /*********/
+
+    #[test]
+    fn test_to_svg()
+    {
+        let expected = r#"<path d="M 1 0 A 1 1 0 0 1 -1 0 L 1 0 Z" />"#;
+
+        assert_eq!(half_disk().to_svg(), expected);
+    }
+}
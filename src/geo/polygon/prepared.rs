@@ -0,0 +1,396 @@
+use super::super::{Container, GeometryError, Intersecter, SVG};
+use super::super::{Point, Segment, Unit};
+
+use super::any::crossings;
+use super::{Any, Polygon, Prepare, Rectangle};
+
+use std::cell::OnceCell;
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+// Below this many corners, walking every segment is faster than the index.
+
+pub const PREPARED_MIN_LEN: usize = 32;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// A polygon ready for repeated point containment tests: its frame is cached,
+// and its segments are indexed by their x-range so that only the ones which
+// the upward ray of a point may cross are walked. The index is built by the
+// first test needing it. Like `Any`, a polygon with a degenerate segment
+// contains no point.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedAny
+{
+    frame: Result<Rectangle, GeometryError>,
+    index: OnceCell<EdgeIndex>,
+    is_degenerate: bool,
+    polygon: Any
+}
+
+// A centered interval tree of the x-ranges of the segments, whose nodes are
+// stored in a slab indexed by their position.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct EdgeIndex { nodes: Vec<EdgeNode> }
+
+#[derive(Clone, Debug, PartialEq)]
+struct EdgeNode
+{
+    by_max: Vec<(Unit, usize)>,
+    by_min: Vec<(Unit, usize)>,
+    center: Unit,
+    left: Option<usize>,
+    right: Option<usize>
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl PreparedAny {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(polygon: Any) -> Self
+    {
+        let frame = polygon.frame();
+        let is_degenerate = polygon.segments().any(|segment| segment.is_err());
+
+        Self { frame, index: OnceCell::new(), is_degenerate, polygon }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn into_inner(self) -> Any { self.polygon }
+
+    // Only polygons with at least `PREPARED_MIN_LEN` corners are indexed.
+
+    pub fn is_indexed(&self) -> bool
+    {
+        self.polygon.len() >= PREPARED_MIN_LEN
+    }
+
+    pub fn polygon(&self) -> &Any { &self.polygon }
+}
+
+impl EdgeIndex {
+    fn new(polygon: &Any) -> Self
+    {
+        let intervals =
+            polygon
+                .segments()
+                .enumerate()
+                .filter_map(|(i, segment)| Some((i, segment.ok()?)))
+                .map(
+                    |(i, segment)| {
+                        let (a, b) = (segment.start().x, segment.stop().x);
+
+                        (a.min(b), a.max(b), i)
+                    }
+                )
+                .collect();
+
+        let mut ret = Self::default();
+
+        ret.build(intervals);
+
+        ret
+    }
+
+    // The node splits the intervals around the median of their middles, which
+    // the interval holding that middle always overlaps.
+
+    fn build(&mut self, mut intervals: Vec<(Unit, Unit, usize)>)
+        -> Option<usize>
+    {
+        if intervals.is_empty() {
+            return None;
+        }
+
+        let middle = |&(min, max, _): &(Unit, Unit, usize)| (min + max) / 2.;
+
+        intervals.sort_by(|a, b| middle(a).total_cmp(&middle(b)));
+
+        let center = middle(&intervals[intervals.len() / 2]);
+
+        let (mut left, mut right, mut here) =
+            (Vec::new(), Vec::new(), Vec::new());
+
+        for interval in intervals {
+            if interval.1 < center {
+                left.push(interval);
+            } else if interval.0 > center {
+                right.push(interval);
+            } else {
+                here.push(interval);
+            }
+        }
+
+        let mut by_min =
+            here
+                .iter()
+                .map(|&(min, _, i)| (min, i))
+                .collect::<Vec<_>>();
+
+        let mut by_max =
+            here
+                .iter()
+                .map(|&(_, max, i)| (max, i))
+                .collect::<Vec<_>>();
+
+        by_min.sort_by(|a, b| a.0.total_cmp(&b.0));
+        by_max.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let ret = self.nodes.len();
+
+        self.nodes.push(
+            EdgeNode { by_max, by_min, center, left: None, right: None }
+        );
+
+        self.nodes[ret].left = self.build(left);
+        self.nodes[ret].right = self.build(right);
+
+        Some(ret)
+    }
+
+    // Calls `f` with every segment whose x-range contains `x`.
+
+    fn for_each_at<F>(&self, x: Unit, mut f: F) where F: FnMut(usize)
+    {
+        let mut next = (!self.nodes.is_empty()).then_some(0);
+
+        while let Some(id) = next {
+            let node = &self.nodes[id];
+
+            if x < node.center {
+                node
+                    .by_min
+                    .iter()
+                    .take_while(|(min, _)| *min <= x)
+                    .for_each(|&(_, i)| f(i));
+
+                next = node.left;
+            } else if x > node.center {
+                node
+                    .by_max
+                    .iter()
+                    .take_while(|(max, _)| *max >= x)
+                    .for_each(|&(_, i)| f(i));
+
+                next = node.right;
+            } else {
+                node.by_min.iter().for_each(|&(_, i)| f(i));
+
+                next = None;
+            }
+        }
+    }
+}
+
+impl Default for PreparedAny {
+    fn default() -> Self { Self::new(Any::default()) }
+}
+
+impl From<Any> for PreparedAny {
+    fn from(polygon: Any) -> Self { Self::new(polygon) }
+}
+
+/***************/
+/* `Container` */
+/***************/
+
+impl Container for PreparedAny {
+    fn contains(&self, other: &Self) -> bool
+    {
+        other.polygon.points.first().is_some_and(|point| self.contains(point))
+    }
+}
+
+// Gives the same answers as `Any` from the cached frame, and once indexed only
+// adds the crossings of the segments which the ray may meet.
+
+impl Container<Point> for PreparedAny {
+    fn contains(&self, &other: &Point) -> bool
+    {
+        let Ok(frame) = self.frame else {
+            return false;
+        };
+
+        if self.is_degenerate {
+            return false;
+        }
+
+        let y = frame.top_right().y;
+
+        if other.y >= y {
+            return false;
+        }
+
+        let Ok(u) = Segment::try_new(other, (other.x, y).into()) else {
+            return false;
+        };
+
+        let n = self.polygon.len();
+        let mut count = 0;
+
+        let add = |i: usize| {
+            let a = self.polygon.segment(i);
+            let b = self.polygon.segment((i + 1) % n);
+
+            if let (Ok(a), Ok(b)) = (a, b) {
+                count += crossings(a, b, &u);
+            }
+        };
+
+        if self.is_indexed() {
+            let index =
+                self.index.get_or_init(|| EdgeIndex::new(&self.polygon));
+
+            index.for_each_at(other.x, add);
+        } else {
+            (0..n).for_each(add);
+        }
+
+        (count % 2) == 1
+    }
+}
+
+impl Intersecter for PreparedAny {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        self.polygon.intersects(&other.polygon)
+    }
+}
+
+impl Polygon for PreparedAny {
+    fn area(&self) -> Unit { self.polygon.area() }
+    fn len(&self) -> usize { self.polygon.len() }
+    fn is_valid(&self) -> bool { self.polygon.is_valid() }
+
+    fn frame(&self) -> Result<Rectangle, GeometryError> { self.frame }
+}
+
+// Only the polygons with at least `PREPARED_MIN_LEN` corners get an index, so
+// preparing the other ones merely caches their frame.
+
+impl Prepare for Any {
+    type Prepared = PreparedAny;
+
+    fn prepare(self) -> PreparedAny { PreparedAny::new(self) }
+    fn restore(prepared: PreparedAny) -> Self { prepared.into_inner() }
+}
+
+impl Prepare for PreparedAny {
+    type Prepared = Self;
+
+    fn prepare(self) -> Self { self }
+    fn restore(prepared: Self) -> Self { prepared }
+}
+
+impl SVG for PreparedAny {
+    fn to_svg(&self) -> String { self.polygon.to_svg() }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    // A star whose corners alternate between two radii, with a few of them on
+    // the same verticals as others to exercise the edge cases of the ray.
+
+    fn generate_star(corner_count: usize) -> Any
+    {
+        let points =
+            (0..corner_count)
+                .map(
+                    |i| {
+                        let angle =
+                            std::f64::consts::TAU * i as Unit
+                                / corner_count as Unit;
+
+                        let radius = if i % 2 == 0 { 10. } else { 4. };
+
+                        Point {
+                            x: (radius * angle.cos()).round(),
+                            y: radius * angle.sin()
+                        }
+                    }
+                )
+                .collect();
+
+        Any { points }
+    }
+
+    #[test]
+    fn test_contains_like_any()
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for corner_count in [8, PREPARED_MIN_LEN, 64, 200] {
+            let polygon = generate_star(corner_count);
+            let testing = PreparedAny::new(polygon.clone());
+
+            assert_eq!(testing.is_indexed(), corner_count >= PREPARED_MIN_LEN);
+
+            let corners = polygon.points.clone();
+
+            let points =
+                (0..2000)
+                    .map(
+                        |_| Point {
+                            x: rng.gen_range(-11..=11) as Unit,
+                            y: rng.gen_range(-11.0..11.)
+                        }
+                    )
+                    .chain(corners);
+
+            for point in points {
+                assert_eq!(
+                    testing.contains(&point),
+                    polygon.contains(&point),
+                    "{:?}",
+                    point
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_is_not_indexed()
+    {
+        let polygon = Rectangle::square(Point::default(), 2.).polygon();
+        let testing = PreparedAny::from(polygon.clone());
+
+        assert!(!testing.is_indexed());
+        assert_eq!(testing.frame(), polygon.frame());
+        assert!(testing.contains(&Point { x: 1., y: 1. }));
+        assert_eq!(testing.into_inner(), polygon);
+    }
+
+    #[test]
+    fn test_degenerate()
+    {
+        let mut polygon = generate_star(PREPARED_MIN_LEN);
+
+        polygon.points.insert(1, polygon.points[0]);
+
+        let testing = PreparedAny::new(polygon.clone());
+
+        assert!(testing.frame().is_ok());
+        assert!(!testing.contains(&Point::default()));
+        assert!(PreparedAny::new(Any::default()).frame().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use super::{Any, Polygon};
 
-use super::super::{Container, SVG};
+use super::super::{Container, GeometryError, Intersecter, SVG};
 use super::super::{Point, Segment, Unit};
 
 /**************/
@@ -22,9 +22,34 @@ impl Rectangle {
 
     pub fn new(bottom_left: Point, top_right: Point) -> Self
     {
-        assert_ne!(bottom_left, top_right);
+        Self::try_new(bottom_left, top_right).unwrap()
+    }
+
+    // One side may have no length, so that flat polygons still have a frame.
+
+    pub fn try_new(bottom_left: Point, top_right: Point)
+        -> Result<Self, GeometryError>
+    {
+        let is_crossed =
+            (bottom_left.x > top_right.x) || (bottom_left.y > top_right.y);
+
+        if (bottom_left == top_right) || is_crossed {
+            Err(GeometryError::DegenerateRectangle)
+        } else {
+            Ok(Self { bottom_left, top_right })
+        }
+    }
+
+    pub fn from_corners(a: Point, b: Point) -> Result<Self, GeometryError>
+    {
+        if (a.x == b.x) || (a.y == b.y) {
+            Err(GeometryError::DegenerateRectangle)
+        } else {
+            let bottom_left = Point { x: a.x.min(b.x), y: a.y.min(b.y) };
+            let top_right = Point { x: a.x.max(b.x), y: a.y.max(b.y) };
 
-        Self { bottom_left, top_right }
+            Ok(Self { bottom_left, top_right })
+        }
     }
 
     pub fn square(bottom_left: Point, side: Unit) -> Self
@@ -40,16 +65,17 @@ impl Rectangle {
 
     pub fn bottom_left(&self) -> Point { self.bottom_left }
 
+    // Both halves share the dividing segment, computed once so that they
+    // meet exactly.
+
     pub fn divide_horizontally(&self) -> (Self, Segment, Self)
     {
-        let h = self.height() / 2.;
+        let y = self.bottom_left.y + self.height() / 2.;
 
-        let (x, y) = self.top_right.into();
-        let stop = Point { x, y: y - h };
-        let top = Rectangle::new(self.bottom_left, stop);
+        let start = Point { x: self.bottom_left.x, y };
+        let stop = Point { x: self.top_right.x, y };
 
-        let (x, y) = self.bottom_left.into();
-        let start = Point { x, y: y + h };
+        let top = Rectangle::new(self.bottom_left, stop);
         let bottom = Rectangle::new(start, self.top_right);
 
         (top, Segment::new(start, stop), bottom)
@@ -57,19 +83,56 @@ impl Rectangle {
 
     pub fn divide_vertically(&self) -> (Self, Segment, Self)
     {
-        let w = self.width() / 2.;
+        let x = self.bottom_left.x + self.width() / 2.;
 
-        let (x, y) = self.top_right.into();
-        let stop = Point { x: x - w, y };
-        let left = Rectangle::new(self.bottom_left, stop);
+        let start = Point { x, y: self.bottom_left.y };
+        let stop = Point { x, y: self.top_right.y };
 
-        let (x, y) = self.bottom_left.into();
-        let start = Point { x: x + w, y };
+        let left = Rectangle::new(self.bottom_left, stop);
         let right = Rectangle::new(start, self.top_right);
 
         (left, Segment::new(start, stop), right)
     }
 
+    pub fn expand(&self, margin: Unit) -> Result<Self, GeometryError>
+    {
+        let (xmin, ymin) = self.bottom_left.into();
+        let (xmax, ymax) = self.top_right.into();
+
+        let bottom_left = Point { x: xmin - margin, y: ymin - margin };
+        let top_right = Point { x: xmax + margin, y: ymax + margin };
+
+        if (bottom_left.x >= top_right.x) || (bottom_left.y >= top_right.y) {
+            Err(GeometryError::DegenerateRectangle)
+        } else {
+            Self::from_corners(bottom_left, top_right)
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self>
+    {
+        let xmin = self.bottom_left.x.max(other.bottom_left.x);
+        let ymin = self.bottom_left.y.max(other.bottom_left.y);
+        let xmax = self.top_right.x.min(other.top_right.x);
+        let ymax = self.top_right.y.min(other.top_right.y);
+
+        if (xmin < xmax) && (ymin < ymax) {
+            Some(Self::new((xmin, ymin).into(), (xmax, ymax).into()))
+        } else {
+            None
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self
+    {
+        let xmin = self.bottom_left.x.min(other.bottom_left.x);
+        let ymin = self.bottom_left.y.min(other.bottom_left.y);
+        let xmax = self.top_right.x.max(other.top_right.x);
+        let ymax = self.top_right.y.max(other.top_right.y);
+
+        Self::new((xmin, ymin).into(), (xmax, ymax).into())
+    }
+
     pub fn is_square(&self) -> bool { self.height() == self.width() }
     pub fn height(&self) -> Unit { self.top_right.y - self.bottom_left.y }
 
@@ -94,7 +157,9 @@ impl Rectangle {
 
 impl Container for Rectangle {
     fn contains(&self, other: &Self) -> bool
-        { self.contains(&other.bottom_left) }
+    {
+        self.contains(&other.bottom_left) && self.contains(&other.top_right)
+    }
 }
 
 impl Container<Point> for Rectangle {
@@ -108,10 +173,20 @@ impl Container<Point> for Rectangle {
     }
 }
 
+impl Intersecter for Rectangle {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        (self.bottom_left.x <= other.top_right.x) &&
+            (other.bottom_left.x <= self.top_right.x) &&
+            (self.bottom_left.y <= other.top_right.y) &&
+            (other.bottom_left.y <= self.top_right.y)
+    }
+}
+
 impl Polygon for Rectangle {
     fn len(&self) -> usize { 4 }
     fn area(&self) -> Unit { self.height() * self.width() }
-    fn frame(&self) -> Rectangle { *self }
+    fn frame(&self) -> Result<Rectangle, GeometryError> { Ok(*self) }
     fn is_valid(&self) -> bool { true }
 }
 
@@ -136,6 +211,217 @@ mod tests
 {
     use super::*;
 
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    #[test]
+    fn test_from_corners()
+    {
+        let expected =
+            Rectangle::new(
+                Point { x: -1., y: -1. },
+                Point { x: 2., y: 1. }
+            );
+
+        let testing =
+            Rectangle::from_corners(
+                Point { x: 2., y: -1. },
+                Point { x: -1., y: 1. }
+            );
+
+        assert_eq!(testing, Ok(expected));
+    }
+
+    #[test]
+    fn test_try_new()
+    {
+        let (a, b) = (Point::default(), Point { x: 1., y: 0. });
+
+        assert_eq!(Rectangle::try_new(a, b), Ok(Rectangle::new(a, b)));
+
+        assert_eq!(
+            Rectangle::try_new(b, a),
+            Err(GeometryError::DegenerateRectangle)
+        );
+
+        assert_eq!(
+            Rectangle::try_new(a, a),
+            Err(GeometryError::DegenerateRectangle)
+        );
+    }
+
+    #[test]
+    fn test_from_corners_equal()
+    {
+        let testing =
+            Rectangle::from_corners(Point::default(), Point::default());
+
+        assert_eq!(testing, Err(GeometryError::DegenerateRectangle));
+    }
+
+    #[test]
+    fn test_from_corners_flat()
+    {
+        let a = Point::default();
+
+        for b in [Point { x: 0., y: 1. }, Point { x: -1., y: 0. }] {
+            assert_eq!(
+                Rectangle::from_corners(a, b),
+                Err(GeometryError::DegenerateRectangle)
+            );
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    #[test]
+    fn test_divide_horizontally()
+    {
+        let testing = Rectangle::new(Point::default(), Point { x: 4., y: 2. });
+
+        let (top, segment, bottom) = testing.divide_horizontally();
+
+        let expected_top =
+            Rectangle::new(Point::default(), Point { x: 4., y: 1. });
+
+        let expected_bottom =
+            Rectangle::new(Point { x: 0., y: 1. }, Point { x: 4., y: 2. });
+
+        assert_eq!(top, expected_top);
+        assert_eq!(bottom, expected_bottom);
+        assert_eq!(segment, Segment::new((0., 1.).into(), (4., 1.).into()));
+    }
+
+    #[test]
+    fn test_divide_vertically()
+    {
+        let testing =
+            Rectangle::new(Point { x: 0.1, y: 0. }, Point { x: 0.7, y: 2. });
+
+        let (left, _, right) = testing.divide_vertically();
+
+        assert_eq!(left.top_right().x, right.bottom_left().x);
+        assert_eq!(left.bottom_left(), testing.bottom_left());
+        assert_eq!(right.top_right(), testing.top_right());
+    }
+
+    #[test]
+    fn test_expand()
+    {
+        let expected =
+            Rectangle::new(Point { x: -1., y: -1. }, Point { x: 2., y: 2. });
+        let testing = Rectangle::square(Point::default(), 1.).expand(1.);
+
+        assert_eq!(testing, Ok(expected));
+    }
+
+    #[test]
+    fn test_expand_too_much()
+    {
+        let testing = Rectangle::square(Point::default(), 1.).expand(-1.);
+
+        assert_eq!(testing, Err(GeometryError::DegenerateRectangle));
+    }
+
+    #[test]
+    fn test_expand_flat()
+    {
+        let testing =
+            Rectangle::new(Point::default(), Point { x: 2., y: 1. })
+                .expand(-0.5);
+
+        assert_eq!(testing, Err(GeometryError::DegenerateRectangle));
+    }
+
+    #[test]
+    fn test_intersection()
+    {
+        let a = Rectangle::square(Point::default(), 2.);
+        let b = Rectangle::square(Point { x: 1., y: 1. }, 2.);
+
+        let expected = Rectangle::square(Point { x: 1., y: 1. }, 1.);
+
+        assert_eq!(a.intersection(&b), Some(expected));
+        assert_eq!(b.intersection(&a), Some(expected));
+    }
+
+    #[test]
+    fn test_intersection_disjoint()
+    {
+        let a = Rectangle::square(Point::default(), 1.);
+        let b = Rectangle::square(Point { x: 1., y: 0. }, 1.);
+
+        assert_eq!(a.intersection(&b), None);
+    }
+
+    #[test]
+    fn test_union()
+    {
+        let a = Rectangle::square(Point::default(), 1.);
+        let b = Rectangle::square(Point { x: 2., y: -1. }, 1.);
+
+        let expected =
+            Rectangle::new(Point { x: 0., y: -1. }, Point { x: 3., y: 1. });
+
+        assert_eq!(a.union(&b), expected);
+        assert_eq!(b.union(&a), expected);
+    }
+
+    /***************/
+    /* `Container` */
+    /***************/
+
+    #[test]
+    fn test_contains()
+    {
+        let a = Rectangle::square(Point::default(), 3.);
+        let b = Rectangle::square(Point { x: 1., y: 1. }, 1.);
+
+        assert!(a.contains(&b));
+        assert!(!b.contains(&a));
+    }
+
+    #[test]
+    fn test_contains_overflowing()
+    {
+        let a = Rectangle::square(Point::default(), 3.);
+        let b = Rectangle::square(Point { x: 1., y: 1. }, 3.);
+
+        assert!(!a.contains(&b));
+    }
+
+    /*****************/
+    /* `Intersecter` */
+    /*****************/
+
+    #[test]
+    fn test_intersects()
+    {
+        let a = Rectangle::square(Point::default(), 2.);
+        let b = Rectangle::square(Point { x: 1., y: 1. }, 2.);
+        let c = Rectangle::square(Point { x: 2., y: 0. }, 1.);
+
+        assert!(a.intersects(&b));
+        assert!(a.intersects(&c));
+    }
+
+    #[test]
+    fn test_intersects_disjoint()
+    {
+        let a = Rectangle::square(Point::default(), 1.);
+        let b = Rectangle::square(Point { x: 3., y: 3. }, 1.);
+
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+    }
+
+    /*********/
+    /* `SVG` */
+    /*********/
+
     #[test]
     fn test_to_svg()
     {
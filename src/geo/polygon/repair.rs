@@ -0,0 +1,415 @@
+use super::super::{Intersecter, Point, Unit, Vector};
+use super::{Any, Polygon};
+
+use derive_more::Display;
+
+use std::mem::discriminant;
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+// How many times the rings split from a polygon may be split again.
+
+const MAX_SPLITS: usize = 8;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+pub enum Fix
+{
+    #[display(fmt = "collinear corners removed: {}", _0)]
+    Collinear(usize),
+
+    #[display(fmt = "degenerate rings dropped: {}", _0)]
+    Degenerate(usize),
+
+    #[display(fmt = "duplicate corners removed: {}", _0)]
+    Duplicates(usize),
+
+    #[display(fmt = "spikes removed: {}", _0)]
+    Spikes(usize),
+
+    #[display(fmt = "self-intersecting rings split: {}", _0)]
+    Split(usize)
+}
+
+// The valid polygons a polygon was repaired into, and what it took. A polygon
+// which was already valid and has no useless corner is left as is.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Repair { pub fixes: Vec<Fix>, pub polygons: Vec<Any> }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Any {
+    pub fn repair(&self) -> Repair
+    {
+        let mut ret = Repair::default();
+
+        repair_into(self.points.clone(), MAX_SPLITS, &mut ret);
+
+        ret
+    }
+}
+
+impl Fix {
+    fn count(&self) -> usize
+    {
+        match *self {
+            Fix::Collinear(count) => count,
+            Fix::Degenerate(count) => count,
+            Fix::Duplicates(count) => count,
+            Fix::Spikes(count) => count,
+            Fix::Split(count) => count
+        }
+    }
+
+    fn with_count(&self, count: usize) -> Self
+    {
+        match self {
+            Fix::Collinear(_) => Fix::Collinear(count),
+            Fix::Degenerate(_) => Fix::Degenerate(count),
+            Fix::Duplicates(_) => Fix::Duplicates(count),
+            Fix::Spikes(_) => Fix::Spikes(count),
+            Fix::Split(_) => Fix::Split(count)
+        }
+    }
+}
+
+impl Repair {
+    pub fn is_unchanged(&self) -> bool { self.fixes.is_empty() }
+
+    // Fixes of the same kind are summed up, in the order they first occur.
+
+    fn record(&mut self, fix: Fix)
+    {
+        if fix.count() == 0 {
+            return;
+        }
+
+        let same =
+            self
+                .fixes
+                .iter_mut()
+                .find(|other| discriminant(*other) == discriminant(&fix));
+
+        match same {
+            Some(other) => {
+                *other = other.with_count(other.count() + fix.count());
+            },
+
+            None => self.fixes.push(fix)
+        }
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+fn repair_into(mut points: Vec<Point>, splits: usize, ret: &mut Repair)
+{
+    clean(&mut points, ret);
+
+    let polygon = Any { points };
+
+    // The area of a self-intersecting ring may vanish even though it is not
+    // degenerate, so it is only checked for valid ones.
+
+    if polygon.len() < 3 {
+        ret.record(Fix::Degenerate(1));
+    } else if polygon.is_valid() {
+        if polygon.area() == 0. {
+            ret.record(Fix::Degenerate(1));
+        } else {
+            ret.polygons.push(polygon);
+        }
+    } else if splits == 0 {
+        ret.record(Fix::Degenerate(1));
+    } else {
+        ret.record(Fix::Split(1));
+
+        for ring in split(&polygon) {
+            repair_into(ring, splits - 1, ret);
+        }
+    }
+}
+
+// Removes the corners equal to the previous one, and the corners aligned with
+// their neighbours, be they in between (collinear) or not (spikes).
+
+fn clean(points: &mut Vec<Point>, ret: &mut Repair)
+{
+    #[derive(PartialEq)]
+    enum Kind { Collinear, Spike, Turn }
+
+    fn kind(a: Point, b: Point, c: Point) -> Kind
+    {
+        let u = Vector::from((a, b));
+        let v = Vector::from((b, c));
+
+        if u.det(&v) != 0. {
+            Kind::Turn
+        } else if u.dot(&v) > 0. {
+            Kind::Collinear
+        } else {
+            Kind::Spike
+        }
+    }
+
+    let (mut collinear, mut duplicates, mut spikes) = (0, 0, 0);
+
+    let mut remove = |kind: Kind| {
+        match kind {
+            Kind::Collinear => collinear += 1,
+            Kind::Spike => spikes += 1,
+            Kind::Turn => duplicates += 1
+        }
+    };
+
+    let mut stack = Vec::<Point>::with_capacity(points.len());
+
+    for &point in points.iter() {
+        stack.push(point);
+
+        loop {
+            let n = stack.len();
+
+            if n >= 2 && stack[n - 2] == stack[n - 1] {
+                stack.pop();
+                remove(Kind::Turn);
+            } else if n >= 3 {
+                let k = kind(stack[n - 3], stack[n - 2], stack[n - 1]);
+
+                if k == Kind::Turn {
+                    break;
+                }
+
+                stack.remove(n - 2);
+                remove(k);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // The ring closes on its first corner, which needs the same checks.
+
+    while stack.len() >= 3 {
+        let n = stack.len();
+
+        if stack[n - 1] == stack[0] {
+            stack.pop();
+            remove(Kind::Turn);
+        } else if kind(stack[n - 2], stack[n - 1], stack[0]) != Kind::Turn {
+            let k = kind(stack[n - 2], stack[n - 1], stack[0]);
+
+            stack.pop();
+            remove(k);
+        } else if kind(stack[n - 1], stack[0], stack[1]) != Kind::Turn {
+            let k = kind(stack[n - 1], stack[0], stack[1]);
+
+            stack.remove(0);
+            remove(k);
+        } else {
+            break;
+        }
+    }
+
+    ret.record(Fix::Duplicates(duplicates));
+    ret.record(Fix::Collinear(collinear));
+    ret.record(Fix::Spikes(spikes));
+
+    *points = stack;
+}
+
+// Adds the points where the polygon crosses itself as corners of both crossing
+// segments, and then cuts the ring into loops at every corner it goes through
+// twice. Loops too small to have an area are left out.
+
+fn split(polygon: &Any) -> Vec<Vec<Point>>
+{
+    let n = polygon.len();
+    let points = &polygon.points;
+
+    let mut cuts = vec! [Vec::<(Unit, Point)>::new(); n];
+
+    let at = |i: usize, t: Unit| {
+        let r = Vector::from((points[i], points[(i + 1) % n]));
+
+        Point::from(Vector::from(points[i]) + r * t)
+    };
+
+    // The position along the segment `i` of a point lying on its line.
+
+    let along = |i: usize, point: Point| {
+        let r = Vector::from((points[i], points[(i + 1) % n]));
+
+        Vector::from((points[i], point)).dot(&r) / r.squared_norm()
+    };
+
+    for i in 0..n {
+        for j in (i + 2)..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+
+            let (Ok(a), Ok(b)) = (polygon.segment(i), polygon.segment(j)) else {
+                continue;
+            };
+
+            if !a.intersects(&b) {
+                continue;
+            }
+
+            let r = Vector::from(a);
+            let s = Vector::from(b);
+            let qp = Vector::from((points[i], points[j]));
+
+            let denominator = r.det(&s);
+
+            if denominator != 0. {
+                let t = (qp.det(&s) / denominator).clamp(0., 1.);
+                let u = (qp.det(&r) / denominator).clamp(0., 1.);
+                let point = at(i, t);
+
+                cuts[i].push((t, point));
+                cuts[j].push((u, point));
+            } else if qp.det(&r) == 0. {
+                for (k, l) in [(i, j), (j, i)] {
+                    for point in [points[l], points[(l + 1) % n]] {
+                        let t = along(k, point);
+
+                        if 0. < t && t < 1. {
+                            cuts[k].push((t, point));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut ring = Vec::with_capacity(2 * n);
+
+    for (i, cuts) in cuts.iter_mut().enumerate() {
+        ring.push(points[i]);
+
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for &(_, point) in cuts.iter() {
+            let next = points[(i + 1) % n];
+
+            if ring.last() != Some(&point) && point != next {
+                ring.push(point);
+            }
+        }
+    }
+
+    let mut ret = Vec::new();
+    let mut path = Vec::<Point>::with_capacity(ring.len());
+
+    for point in ring {
+        if let Some(k) = path.iter().position(|&other| other == point) {
+            ret.push(path.split_off(k));
+        }
+
+        path.push(point);
+    }
+
+    ret.push(path);
+    ret.retain(|ring| ring.len() >= 3);
+
+    ret
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::Rectangle;
+
+    fn polygon(points: &[(Unit, Unit)]) -> Any
+    {
+        Any { points: points.iter().map(|&p| p.into()).collect() }
+    }
+
+    #[test]
+    fn test_repair_valid()
+    {
+        let square = Rectangle::square(Point::default(), 2.).polygon();
+        let testing = square.repair();
+
+        assert!(testing.is_unchanged());
+        assert_eq!(testing.polygons, vec! [square]);
+    }
+
+    #[test]
+    fn test_repair_corners()
+    {
+        let testing =
+            polygon(
+                &[
+                    (0., 0.),
+                    (1., 0.),
+                    (1., 0.),
+                    (2., 0.),
+                    (2., 2.),
+                    (3., 3.),
+                    (2., 2.),
+                    (0., 2.),
+                    (0., 0.)
+                ]
+            )
+            .repair();
+
+        let expected = Repair {
+            fixes: vec! [
+                Fix::Duplicates(3),
+                Fix::Collinear(1),
+                Fix::Spikes(1)
+            ],
+            polygons: vec! [
+                polygon(&[(0., 0.), (2., 0.), (2., 2.), (0., 2.)])
+            ]
+        };
+
+        assert!(!polygon(&[(0., 0.), (1., 0.), (1., 0.), (0., 1.)]).is_valid());
+        assert_eq!(testing, expected);
+    }
+
+    #[test]
+    fn test_repair_split()
+    {
+        let bow_tie = polygon(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.)]);
+
+        assert!(!bow_tie.is_valid());
+
+        let testing = bow_tie.repair();
+
+        assert_eq!(testing.fixes, vec! [Fix::Split(1)]);
+        assert_eq!(testing.polygons.len(), 2);
+
+        for polygon in testing.polygons {
+            assert!(polygon.is_valid());
+            assert_eq!(polygon.len(), 3);
+            assert!((polygon.area() - 1.).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_repair_degenerate()
+    {
+        let testing = polygon(&[(0., 0.), (1., 1.), (2., 2.)]).repair();
+
+        assert!(testing.polygons.is_empty());
+        assert_eq!(testing.fixes.last(), Some(&Fix::Degenerate(1)));
+    }
+}
@@ -0,0 +1,216 @@
+use super::{Any, Circle, Curved, Polygon, Prepare, Rectangle};
+
+use super::super::{Container, GeometryError, Intersecter, SVG};
+use super::super::{Edge, Point, Unit};
+
+use derive_more::From;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum Shape { Any(Any), Circle(Circle), Curved(Curved) }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Shape {
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn anchor(&self) -> Point
+    {
+        match self {
+            Shape::Any(any) => any.points[0],
+            Shape::Circle(circle) => *circle.edge().start(),
+            Shape::Curved(curved) => *curved.edges[0].start()
+        }
+    }
+
+    pub fn flatten(&self, tolerance: Unit) -> Any
+    {
+        match self {
+            Shape::Any(any) => any.clone(),
+            Shape::Circle(circle) => circle.flatten(tolerance),
+            Shape::Curved(curved) => curved.flatten(tolerance)
+        }
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn edges(&self) -> Vec<Edge>
+    {
+        match self {
+            Shape::Any(any) =>
+                any.segments().flatten().map(Edge::from).collect(),
+            Shape::Circle(circle) => vec! [circle.edge()],
+            Shape::Curved(curved) => curved.edges.clone()
+        }
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self { Shape::Any(Any::default()) }
+}
+
+/***************/
+/* `Container` */
+/***************/
+
+impl Container for Shape {
+    fn contains(&self, other: &Self) -> bool { self.contains(&other.anchor()) }
+}
+
+impl Container<Point> for Shape {
+    fn contains(&self, other: &Point) -> bool
+    {
+        match self {
+            Shape::Any(any) => any.contains(other),
+            Shape::Circle(circle) => circle.contains(other),
+            Shape::Curved(curved) => curved.contains(other)
+        }
+    }
+}
+
+impl Intersecter for Shape {
+    fn intersects(&self, other: &Self) -> bool
+    {
+        match (self, other) {
+            (Shape::Any(a), Shape::Any(b)) => a.intersects(b),
+            (Shape::Circle(a), Shape::Circle(b)) => a.intersects(b),
+            (Shape::Curved(a), Shape::Curved(b)) => a.intersects(b),
+
+            _ => {
+                let edges = other.edges();
+
+                self
+                    .edges()
+                    .iter()
+                    .any(|a| edges.iter().any(|b| a.intersects(b)))
+            }
+        }
+    }
+}
+
+impl Polygon for Shape {
+    fn len(&self) -> usize
+    {
+        match self {
+            Shape::Any(any) => any.len(),
+            Shape::Circle(circle) => circle.len(),
+            Shape::Curved(curved) => curved.len()
+        }
+    }
+
+    fn area(&self) -> Unit
+    {
+        match self {
+            Shape::Any(any) => any.area(),
+            Shape::Circle(circle) => circle.area(),
+            Shape::Curved(curved) => curved.area()
+        }
+    }
+
+    fn is_valid(&self) -> bool
+    {
+        match self {
+            Shape::Any(any) => any.is_valid(),
+            Shape::Circle(circle) => circle.is_valid(),
+            Shape::Curved(curved) => curved.is_valid()
+        }
+    }
+
+    fn frame(&self) -> Result<Rectangle, GeometryError>
+    {
+        match self {
+            Shape::Any(any) => any.frame(),
+            Shape::Circle(circle) => circle.frame(),
+            Shape::Curved(curved) => curved.frame()
+        }
+    }
+}
+
+impl Prepare for Shape {
+    type Prepared = Self;
+
+    fn prepare(self) -> Self { self }
+    fn restore(prepared: Self) -> Self { prepared }
+}
+
+impl SVG for Shape {
+    fn to_svg(&self) -> String
+    {
+        match self {
+            Shape::Any(any) => any.to_svg(),
+            Shape::Circle(circle) => circle.to_svg(),
+            Shape::Curved(curved) => curved.to_svg()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn square(x: Unit, y: Unit, side: Unit) -> Shape
+    {
+        Rectangle::square(Point { x, y }, side).polygon().into()
+    }
+
+    fn circle(x: Unit, y: Unit, radius: Unit) -> Shape
+    {
+        Circle::new(Point { x, y }, radius).into()
+    }
+
+    /***************/
+    /* `Container` */
+    /***************/
+
+    #[test]
+    fn test_contains_mixed()
+    {
+        let outer = square(0., 0., 10.);
+        let hole = circle(5., 5., 2.);
+        let inner = square(4.5, 4.5, 1.);
+
+        assert!(outer.contains(&hole));
+        assert!(hole.contains(&inner));
+        assert!(!hole.contains(&outer));
+        assert!(!inner.contains(&hole));
+    }
+
+    /*****************/
+    /* `Intersecter` */
+    /*****************/
+
+    #[test]
+    fn test_intersects_mixed()
+    {
+        let a = square(0., 0., 2.);
+        let b = circle(2., 1., 0.5);
+        let c = circle(1., 1., 0.5);
+
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&a));
+        assert!(!a.intersects(&c));
+    }
+
+    /*************/
+    /* `Polygon` */
+    /*************/
+
+    #[test]
+    fn test_flatten()
+    {
+        let testing = circle(0., 0., 1.).flatten(0.001);
+
+        assert!(testing.is_valid());
+        assert_eq!(square(0., 0., 1.).flatten(0.001).len(), 4);
+    }
+}
@@ -0,0 +1,230 @@
+use super::polygon::Rectangle;
+use super::{Container, Intersecter};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const CAPACITY: usize = 8;
+const MAX_DEPTH: usize = 16;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// A region quadtree of frames, each one given with an id. A frame is stored in
+// the deepest cell strictly containing it, and a cell is divided into four
+// once it holds more than `CAPACITY` frames.
+
+#[derive(Clone, Debug)]
+pub struct Quadtree
+{
+    bounds: Rectangle,
+    children: Option<Box<[Quadtree; 4]>>,
+    depth: usize,
+    entries: Vec<(Rectangle, usize)>
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Quadtree {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(bounds: Rectangle) -> Self { Self::with_depth(bounds, 0) }
+
+    // The tree whose bounds are the smallest square containing all of
+    // `frames`, up to rounding.
+
+    pub fn from_frames<I>(frames: I) -> Option<Self>
+        where I: IntoIterator<Item = Rectangle>
+    {
+        let frames = frames.into_iter().collect::<Vec<_>>();
+
+        let union =
+            frames
+                .iter()
+                .copied()
+                .reduce(|a, b| a.union(&b))?;
+
+        let side = union.width().max(union.height());
+        let mut ret = Self::new(Rectangle::square(union.bottom_left(), side));
+
+        for (id, frame) in frames.into_iter().enumerate() {
+            ret.insert(frame, id);
+        }
+
+        Some(ret)
+    }
+
+    fn with_depth(bounds: Rectangle, depth: usize) -> Self
+    {
+        Self { bounds, children: None, depth, entries: Vec::new() }
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    pub fn insert(&mut self, frame: Rectangle, id: usize)
+    {
+        if let Some(child) = self.child_containing(&frame) {
+            child.insert(frame, id);
+        } else {
+            self.entries.push((frame, id));
+
+            if self.entries.len() > CAPACITY && self.depth < MAX_DEPTH {
+                self.divide();
+            }
+        }
+    }
+
+    fn divide(&mut self)
+    {
+        if self.children.is_some() {
+            return;
+        }
+
+        let (left, _, right) = self.bounds.divide_vertically();
+        let (top_left, _, bottom_left) = left.divide_horizontally();
+        let (top_right, _, bottom_right) = right.divide_horizontally();
+
+        let depth = self.depth + 1;
+
+        self.children =
+            Some(
+                Box::new(
+                    [top_left, top_right, bottom_left, bottom_right]
+                        .map(|bounds| Self::with_depth(bounds, depth))
+                )
+            );
+
+        for (frame, id) in std::mem::take(&mut self.entries) {
+            self.insert(frame, id);
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn bounds(&self) -> Rectangle { self.bounds }
+
+    fn child_containing(&mut self, frame: &Rectangle) -> Option<&mut Self>
+    {
+        self
+            .children
+            .as_deref_mut()?
+            .iter_mut()
+            .find(|child| child.bounds.contains(frame))
+    }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn len(&self) -> usize
+    {
+        self.entries.len() +
+            self
+                .children
+                .iter()
+                .flat_map(|children| children.iter())
+                .map(Self::len)
+                .sum::<usize>()
+    }
+
+    // The ids of the frames intersecting `frame`, edges included.
+
+    pub fn query(&self, frame: &Rectangle) -> Vec<usize>
+    {
+        let mut ret = Vec::new();
+
+        self.query_into(frame, &mut ret);
+
+        ret
+    }
+
+    fn query_into(&self, frame: &Rectangle, ret: &mut Vec<usize>)
+    {
+        if !self.bounds.intersects(frame) {
+            return;
+        }
+
+        ret.extend(
+            self
+                .entries
+                .iter()
+                .filter(|(other, _)| other.intersects(frame))
+                .map(|&(_, id)| id)
+        );
+
+        for child in self.children.iter().flat_map(|c| c.iter()) {
+            child.query_into(frame, ret);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::Point;
+
+    #[test]
+    fn test_from_frames()
+    {
+        let frames =
+            (0..100)
+                .map(|i| i as f64)
+                .map(|i| Rectangle::square(Point { x: i, y: 2. * i }, 0.5))
+                .collect::<Vec<_>>();
+
+        let testing = Quadtree::from_frames(frames.clone()).unwrap();
+
+        assert_eq!(testing.len(), frames.len());
+        let bounds = testing.bounds();
+
+        assert!((bounds.width() - bounds.height()).abs() < 1e-9);
+        assert!(testing.children.is_some());
+
+        for (id, frame) in frames.iter().enumerate() {
+            assert!(testing.query(frame).contains(&id));
+        }
+
+        assert!(Quadtree::from_frames([]).is_none());
+    }
+
+    #[test]
+    fn test_query()
+    {
+        let frames =
+            (0..10)
+                .flat_map(|x| (0..10).map(move |y| (x as f64, y as f64)))
+                .map(|(x, y)| Rectangle::square(Point { x, y }, 0.5))
+                .collect::<Vec<_>>();
+
+        let testing = Quadtree::from_frames(frames.clone()).unwrap();
+
+        let query =
+            Rectangle::new(Point { x: 2.2, y: 3.2 }, Point { x: 4.1, y: 4.1 });
+
+        let mut expected =
+            frames
+                .iter()
+                .enumerate()
+                .filter(|(_, frame)| frame.intersects(&query))
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+
+        let mut result = testing.query(&query);
+
+        expected.sort_unstable();
+        result.sort_unstable();
+
+        assert_eq!(result, expected);
+        assert_eq!(result.len(), 6);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Container, Distance, Intersecter, SVG};
+use super::{Container, Distance, GeometryError, Intersecter, SVG};
 use super::{Point, Unit, Vector};
 use super::are_ccw;
 
@@ -26,9 +26,19 @@ impl Segment {
 
     pub fn new(start: Point, stop: Point) -> Self
     {
-        assert_ne!(start, stop);
+        Self::try_new(start, stop).unwrap()
+    }
+
+    // The ends are compared with the tolerance of `Point`, so that a segment
+    // too short to have a direction is refused.
 
-        Self { start, stop }
+    pub fn try_new(start: Point, stop: Point) -> Result<Self, GeometryError>
+    {
+        if start == stop {
+            Err(GeometryError::DegenerateSegment)
+        } else {
+            Ok(Self { start, stop })
+        }
     }
 
     /***********/
@@ -146,6 +156,29 @@ mod tests
 {
     use super::*;
 
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    #[test]
+    fn test_try_new()
+    {
+        let (a, b) = (Point::default(), Point { x: 1., y: 0. });
+
+        assert_eq!(Segment::try_new(a, b), Ok(Segment::new(a, b)));
+    }
+
+    #[test]
+    fn test_try_new_degenerate()
+    {
+        let a = Point::default();
+        let b = Point { x: Unit::EPSILON / 2., y: 0. };
+
+        let testing = Segment::try_new(a, b);
+
+        assert_eq!(testing, Err(GeometryError::DegenerateSegment));
+    }
+
     /***********/
     /* GETTERS */
     /***********/
@@ -0,0 +1,294 @@
+use super::geo::polygon::Any;
+use super::geo::Container;
+use super::tree::Arena;
+use super::IndexedArena;
+
+use std::collections::HashMap;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// A hierarchy kept up to date polygon after polygon. Polygons are expected to
+// be either disjoint or nested, as for the `process_*` functions.
+
+#[derive(Clone, Debug)]
+pub struct Hierarchy<P = Any>
+{
+    arena: IndexedArena<P>,
+    ids: HashMap<isize, usize>,
+    next: isize
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl<P> Hierarchy<P> {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new() -> Self where P: Default
+    {
+        let mut arena = Arena::new();
+
+        arena.insert((-1, P::default()));
+
+        Self { arena, ids: HashMap::new(), next: 0 }
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    pub fn insert(&mut self, polygon: P) -> isize where P: Container
+    {
+        let ret = self.next;
+        let node = self.arena.insert((ret, polygon));
+
+        self.ids.insert(ret, node);
+        self.next += 1;
+        self.place(node);
+
+        ret
+    }
+
+    // The children of the removed polygon are handed over to its parent, and
+    // its slot in the arena is taken by the last polygon.
+
+    pub fn remove(&mut self, id: isize) -> Option<P>
+    {
+        let node = self.ids.remove(&id)?;
+
+        self.arena.splice(node);
+
+        let (_, ret) = self.arena.swap_remove(node);
+
+        if node < self.arena.len() {
+            self.ids.insert(self.arena.value(node).0, node);
+        }
+
+        Some(ret)
+    }
+
+    pub fn update(&mut self, id: isize, polygon: P) -> Option<P>
+        where P: Container
+    {
+        let node = *self.ids.get(&id)?;
+
+        self.arena.splice(node);
+
+        let previous = &mut self.arena.value_mut(node).1;
+        let ret = std::mem::replace(previous, polygon);
+
+        self.place(node);
+
+        Some(ret)
+    }
+
+    // Descends to the innermost polygon containing `node`, and then moves
+    // below `node` the children of that polygon which `node` contains.
+
+    fn place(&mut self, node: usize) where P: Container
+    {
+        let arena = &self.arena;
+        let polygon = &arena.value(node).1;
+
+        let mut parent = 0;
+
+        while let Some(child) =
+            arena
+                .children(parent)
+                .find(|&child| arena.value(child).1.contains(polygon))
+        {
+            parent = child;
+        }
+
+        let contained =
+            arena
+                .children(parent)
+                .filter(|&child| polygon.contains(&arena.value(child).1))
+                .collect::<Vec<_>>();
+
+        self.arena.adopt(parent, node).unwrap();
+
+        for child in contained {
+            self.arena.adopt(node, child).unwrap();
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn arena(&self) -> &IndexedArena<P> { &self.arena }
+
+    pub fn get(&self, id: isize) -> Option<&P>
+    {
+        self.ids.get(&id).map(|&node| &self.arena.value(node).1)
+    }
+
+    pub fn is_empty(&self) -> bool { self.ids.is_empty() }
+    pub fn len(&self) -> usize { self.ids.len() }
+
+    // The parent of a top-level polygon is -1.
+
+    pub fn parent(&self, id: isize) -> Option<isize>
+    {
+        let node = *self.ids.get(&id)?;
+        let parent = self.arena.parent(node).unwrap();
+
+        Some(self.arena.value(parent).0)
+    }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn ids(&self) -> impl Iterator<Item = isize> + '_
+    {
+        self.ids.keys().copied()
+    }
+}
+
+impl<P> Default for Hierarchy<P> where P: Default {
+    fn default() -> Self { Self::new() }
+}
+
+impl<P> From<IndexedArena<P>> for Hierarchy<P> {
+    fn from(arena: IndexedArena<P>) -> Self
+    {
+        let ids =
+            (1..arena.len())
+                .map(|node| (arena.value(node).0, node))
+                .collect::<HashMap<_, _>>();
+
+        let next = ids.keys().max().map_or(0, |id| id + 1);
+
+        Self { arena, ids, next }
+    }
+}
+
+#[cfg(all(test, feature = "naive"))]
+mod tests
+{
+    use super::*;
+
+    use super::super::cmd::{generate, process_naive, Family};
+    use super::super::cmd::{Nesting, Parameters};
+    use super::super::geo::Point;
+    use super::super::tree::Node;
+
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn generate_polygons(rng: &mut ChaCha8Rng) -> Vec<Any>
+    {
+        let parameters = Parameters {
+            attempts: 1000,
+            corner_count: 6,
+            dimension: 400.,
+            family: Family::Star,
+            nesting: Nesting { branching: 2, depth: 2, fill: 0.5 },
+            polygon_count: 10,
+            radius: 40.
+        };
+
+        generate(&parameters, rng).unwrap().polygons
+    }
+
+    fn assert_recomputed(hierarchy: &Hierarchy)
+    {
+        let mut ids = hierarchy.ids().collect::<Vec<_>>();
+
+        ids.sort_unstable();
+
+        let nodes =
+            ids
+                .iter()
+                .map(|&id| (id, hierarchy.get(id).unwrap().clone()))
+                .map(Node::new)
+                .collect();
+
+        let expected = process_naive(&nodes);
+
+        for node in 1..expected.len() {
+            let id = expected.value(node).0;
+            let parent = expected.value(expected.parent(node).unwrap()).0;
+
+            assert_eq!(hierarchy.parent(id), Some(parent), "polygon {}", id);
+        }
+    }
+
+    #[test]
+    fn test_from_arena()
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+        let nodes =
+            generate_polygons(&mut rng)
+                .into_iter()
+                .enumerate()
+                .map(|(i, polygon)| Node::new((i as isize, polygon)))
+                .collect::<Vec<_>>();
+
+        let mut testing = Hierarchy::from(process_naive(&nodes));
+
+        let triangle =
+            Any {
+                points: vec! [
+                    Point { x: 1000., y: 1000. },
+                    Point { x: 1001., y: 1000. },
+                    Point { x: 1000., y: 1001. }
+                ]
+            };
+
+        assert_eq!(testing.len(), nodes.len());
+
+        let id = testing.insert(triangle);
+
+        assert_eq!(id, nodes.len() as isize);
+        assert_eq!(testing.parent(id), Some(-1));
+        assert_recomputed(&testing);
+    }
+
+    #[test]
+    fn test_incremental()
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let mut polygons = generate_polygons(&mut rng);
+
+        polygons.shuffle(&mut rng);
+
+        let mut testing = Hierarchy::new();
+
+        for polygon in polygons {
+            testing.insert(polygon);
+            assert_recomputed(&testing);
+        }
+
+        let mut ids = testing.ids().collect::<Vec<_>>();
+
+        ids.sort_unstable();
+        ids.shuffle(&mut rng);
+
+        let (removed, kept) = ids.split_at(ids.len() / 3);
+        let mut spare = Vec::new();
+
+        for &id in removed {
+            spare.push(testing.remove(id).unwrap());
+            assert_recomputed(&testing);
+        }
+
+        assert_eq!(testing.len(), kept.len());
+        assert_eq!(testing.arena().len(), kept.len() + 1);
+        assert_eq!(testing.remove(removed[0]), None);
+
+        for (&id, polygon) in kept.iter().zip(spare) {
+            testing.update(id, polygon).unwrap();
+            assert_recomputed(&testing);
+        }
+    }
+}
@@ -1,7 +1,5 @@
 use geo::polygon::Any;
-use tree::Node;
-
-use itertools::Itertools;
+use tree::{Arena, Node};
 
 use std::fs;
 use std::path::Path;
@@ -13,51 +11,27 @@ use std::rc::Rc;
 
 pub mod cmd;
 pub mod geo;
+pub mod hierarchy;
+pub mod poly;
 pub mod tree;
 
 /*********/
 /* TYPES */
 /*********/
 
-pub type IndexedNode  = Rc<Node<(isize, Any)>>;
-pub type IndexedNodes = Vec<IndexedNode>;
+pub type IndexedNode<P = Any>  = Rc<Node<(isize, P)>>;
+pub type IndexedNodes<P = Any> = Vec<IndexedNode<P>>;
+pub type IndexedArena<P = Any> = Arena<(isize, P)>;
 
 /*************/
 /* FUNCTIONS */
 /*************/
 
+// Panics on malformed input; see `poly::parse` for the errors.
+
 pub fn parse_from_string(str: &str) -> IndexedNodes
 {
-    str
-        .lines()
-        .map(
-            |x| {
-                x
-                    .split_ascii_whitespace()
-                    .collect_tuple()
-                    .unwrap()
-            }
-        )
-        .map(
-            |(p, x, y)| {
-                (
-                    p.parse::<u16>().unwrap(),
-                    x.parse::<geo::Unit>().unwrap(),
-                    y.parse::<geo::Unit>().unwrap()
-                )
-            }
-        )
-        .group_by(|(p, _, _)| *p)
-        .into_iter()
-        .map(
-            |(_, group)| {
-                group.map(|(_, x, y)| (x, y).into()).collect::<Vec<_>>()
-            }
-        )
-        .map(|points| geo::polygon::Any { points })
-        .enumerate()
-        .map(|(i, polygon)| Node::new((i as isize, polygon)))
-        .collect()
+    poly::parse(str).unwrap().into_nodes()
 }
 
 pub fn parse_from_file(path: &Path) -> IndexedNodes
@@ -78,6 +52,7 @@ mod tests
     fn test_parse_from_string()
     {
         let testing = indoc! {"
+            # generate --seed 42
             0 1 1
             0 5 1
             0 5 5
@@ -92,6 +67,7 @@ mod tests
             3 4 4
             3 4 3
             3 3 4
+
         "};
 
         let expected = vec! [
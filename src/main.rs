@@ -1,16 +1,48 @@
-use polygon::cmd::generate;
+use polygon::cmd::bench::{bench, generate_inputs, to_chart, to_csv};
+use polygon::cmd::bench::to_report;
+use polygon::cmd::{adversarial, compare, generate, locate};
+use polygon::cmd::{containment_dag, overlaps as find_overlaps};
+use polygon::cmd::{process_with_policy, DuplicatePolicy};
+use polygon::cmd::{Family, Generation, Nesting, Parameters};
+use polygon::cmd::Registry;
 
-#[cfg(feature = "naive")] use polygon::cmd::process_naive;
-#[cfg(feature = "frames")] use polygon::cmd::process_frames;
+#[cfg(feature = "tracking")] use polygon::cmd::bench::Tracking;
 
-use polygon::geo::SVG;
+use polygon::geo::polygon::{Any, Polygon};
+use polygon::geo::{Point, SVG};
 use polygon::geo::Unit;
 
-use polygon::parse_from_file;
+use polygon::poly::binary::{self, is_binary, read_document};
+use polygon::poly::{self, format_polygon, ParseOptions};
+use polygon::poly::{Attributes, Decimals, Document, Header, VERSION};
 
-use clap::{Parser, Subcommand, ValueEnum};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
-use std::path::Path;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, stdin, BufRead, BufReader, BufWriter, Write};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+const DEFAULT_ATTEMPTS: usize = 1000;
+
+/***********/
+/* STATICS */
+/***********/
+
+// Counting the allocations slows down every command, so `bench` only reports
+// peaks in builds with the `tracking` feature.
+
+#[cfg(feature = "tracking")]
+#[global_allocator]
+static ALLOCATOR: Tracking = Tracking;
 
 /**************/
 /* STRUCTURES */
@@ -19,47 +51,328 @@ use std::path::Path;
 #[derive(Copy, Clone)]
 #[derive(Eq, PartialEq)]
 #[derive(Debug, ValueEnum)]
-enum Algorithm
+enum Duplicates
 {
-    #[cfg(feature = "frames")]
-    Frames,
+    Error,
+    Merge,
+    NestById
+}
+
+#[derive(Copy, Clone)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug, ValueEnum)]
+enum Format { Binary, Text }
 
-    #[cfg(feature = "naive")]
-    Naive
+#[derive(Copy, Clone, Default)]
+#[derive(Eq, PartialEq)]
+#[derive(Debug, ValueEnum)]
+enum Shape
+{
+    Convex,
+    Degenerate,
+    Orthogonal,
+    Sliver,
+    Spiral,
+    #[default] Star
 }
 
 #[derive(Debug, Subcommand)]
 enum Command
 {
+    #[command(about = "Time the algorithms on inputs of increasing size")]
+    Bench {
+        #[arg(
+            long = "algorithm",
+            short,
+            value_parser = parse_algorithm,
+            help = "An algorithm to run (all of them by default)"
+        )]
+
+        algorithms: Vec<String>,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = 8,
+            help = "The maximum number of corners for each generated polygon"
+        )]
+
+        corner_count: usize,
+
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "The number of levels nested inside each generated polygon"
+        )]
+
+        depth: usize,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = 10.,
+            help = "Half of the maximum distance between two corners"
+        )]
+
+        radius: Unit,
+
+        #[arg(
+            long,
+            default_value_t = 5,
+            help = "The number of runs of each algorithm on each input"
+        )]
+
+        runs: usize,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = 0,
+            help = "The seed of the random number generator"
+        )]
+
+        seed: u64,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_values_t = [250, 500, 1000, 2000],
+            help = "The polygon counts of the generated inputs"
+        )]
+
+        sizes: Vec<usize>,
+
+        #[arg(long, help = "Write the measures to this CSV file")]
+        csv: Option<String>,
+
+        #[arg(long, help = "Draw the time against the size to this SVG file")]
+        svg: Option<String>,
+
+        #[arg(help = "Input files to use instead of generated ones")]
+        paths: Vec<String>
+    },
+
+    #[command(about = "Compare the hierarchies built by several algorithms")]
+    Compare {
+        #[arg(
+            long = "algorithm",
+            short,
+            required = true,
+            value_parser = parse_algorithm,
+            help = "An algorithm to compare (at least two are required)"
+        )]
+
+        algorithms: Vec<String>,
+
+        #[arg(long, help = "Render the first disagreement to this SVG file")]
+        svg: Option<String>,
+
+        #[arg(help = "The path of the input file")]
+        path: String
+    },
+
+    #[command(about = "Convert a `.poly` file between text and binary")]
+    Convert {
+        #[arg(long, help = "Write the table of the frames of binary outputs")]
+        frames: bool,
+
+        #[arg(long, help = "Write text coordinates as they were read")]
+        keep_decimals: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "The format to write (the other one by default)"
+        )]
+
+        to: Option<Format>,
+
+        #[arg(help = "The path of the input file")]
+        input: String,
+
+        #[arg(help = "The path of the output file")]
+        output: String
+    },
+
     #[command(about = "Generate a `.poly` file")]
     Generate {
         #[arg(
             long,
             short,
+            required_unless_present = "adversarial",
             help = "The maximum number of corners for each polygon"
         )]
 
-        corner_count: usize,
+        corner_count: Option<usize>,
+
+        #[arg(
+            long,
+            short,
+            required_unless_present = "adversarial",
+            help = "The frame width and height"
+        )]
 
-        #[arg(long, short, help = "The frame width and height")]
-        dimension: Unit,
+        dimension: Option<Unit>,
 
         #[arg(
             long,
             short,
+            required_unless_present = "adversarial",
             help = "Half of the maximum distance between two corners"
         )]
 
-        radius: Unit,
+        radius: Option<Unit>,
+
+        #[arg(
+            long,
+            short,
+            required_unless_present = "adversarial",
+            help = "The polygon count"
+        )]
+
+        polygon_count: Option<usize>,
+
+        #[arg(long, short, help = "The seed of the random number generator")]
+        seed: Option<u64>,
+
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "corner_count",
+                "dimension",
+                "radius",
+                "polygon_count",
+                "seed"
+            ],
+            help = "Emit the labelled cases known to break containment tests"
+        )]
 
-        #[arg(long, short, help = "The polygon count")]
-        polygon_count: usize
+        adversarial: bool,
+
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "The number of levels nested inside each top-level polygon"
+        )]
+
+        depth: usize,
+
+        #[arg(
+            long,
+            default_value_t = 2,
+            help = "The maximum number of children placed inside each polygon"
+        )]
+
+        branching: usize,
+
+        #[arg(
+            long,
+            default_value_t = 0.5,
+            help = "The fraction of the room inside a parent a child may use"
+        )]
+
+        fill: Unit,
+
+        #[arg(long, help = "Write the expected parent of each polygon there")]
+        oracle: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "The family of the generated polygons"
+        )]
+
+        shape: Shape,
+
+        #[arg(
+            long,
+            default_value_t = DEFAULT_ATTEMPTS,
+            help = "The number of tries to place each polygon"
+        )]
+
+        attempts: usize
+    },
+
+    #[command(about = "Find the innermost polygon containing each point")]
+    Locate {
+        #[arg(
+            long,
+            short,
+            value_parser = parse_algorithm,
+            help = "The algorithm building the hierarchy"
+        )]
+
+        algorithm: String,
+
+        #[arg(help = "The path of the input file")]
+        path: String,
+
+        #[arg(
+            allow_hyphen_values = true,
+            help = "The points as `x,y` (read one per line from stdin if none)"
+        )]
+
+        points: Vec<String>
     },
 
     #[command(about = "Process the hierarchy generation")]
     Process {
-        #[arg(long, short, help = "The algorithm used")]
-        algorithm: Algorithm,
+        #[arg(
+            long,
+            short,
+            value_parser = parse_algorithm,
+            help = "The algorithm used"
+        )]
+
+        algorithm: String,
+
+        #[arg(
+            long,
+            help = "Print the parents as `@id parent=` lines with attributes"
+        )]
+
+        annotate: bool,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Write the parents of each polygon in the containment graph"
+        )]
+
+        dag: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "What to do with duplicates, else placed in the input order"
+        )]
+
+        duplicates: Option<Duplicates>,
+
+        #[arg(long, help = "Warn about partially overlapping polygons")]
+        overlaps: bool,
+
+        #[arg(long, help = "Print what the algorithm did after the result")]
+        stats: bool,
+
+        #[arg(
+            long,
+            default_value_t = 0.,
+            requires = "duplicates",
+            help = "The distance below which corners of duplicates match"
+        )]
+
+        tolerance: Unit,
+
+        #[arg(help = "The path of the input file")]
+        path: String
+    },
+
+    #[command(about = "Rewrite a `.poly` file with its polygons made valid")]
+    Repair {
+        #[arg(long, help = "Write coordinates as they were read")]
+        keep_decimals: bool,
 
         #[arg(help = "The path of the input file")]
         path: String
@@ -76,68 +389,795 @@ enum Command
 #[command(about, version)]
 struct Args
 {
+    #[arg(long, help = "List the available algorithms and exit")]
+    list_algorithms: bool,
+
     #[clap(subcommand)]
-    command: Command
+    command: Option<Command>
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl From<Duplicates> for DuplicatePolicy {
+    fn from(duplicates: Duplicates) -> Self
+    {
+        match duplicates {
+            Duplicates::Error => DuplicatePolicy::Error,
+            Duplicates::Merge => DuplicatePolicy::Merge,
+            Duplicates::NestById => DuplicatePolicy::NestById
+        }
+    }
+}
+
+impl From<Shape> for Family {
+    fn from(shape: Shape) -> Self
+    {
+        match shape {
+            Shape::Convex => Family::Convex,
+            Shape::Degenerate => Family::Degenerate,
+            Shape::Orthogonal => Family::Orthogonal,
+            Shape::Sliver => Family::Sliver,
+            Shape::Spiral => Family::Spiral,
+            Shape::Star => Family::Star
+        }
+    }
 }
 
 /*************/
 /* FUNCTIONS */
 /*************/
 
+fn parse_algorithm(name: &str) -> Result<String, String>
+{
+    match Registry::builtin().get(name) {
+        Some(_) => Ok(name.to_owned()),
+        None => Err(String::from("see `--list-algorithms` for the known ones"))
+    }
+}
+
+fn fail<E>(kind: ErrorKind, path: &str, error: E) -> !
+    where E: Display
+{
+    Args::command().error(kind, format!("{}: {}", path, error)).exit()
+}
+
+// Opens `path`, telling whether it is in the binary format.
+
+fn open(path: &str) -> (BufReader<File>, bool)
+{
+    let mut ret =
+        File::open(path)
+            .map(BufReader::new)
+            .unwrap_or_else(|error| fail(ErrorKind::Io, path, error));
+
+    let is_binary =
+        ret
+            .fill_buf()
+            .map(is_binary)
+            .unwrap_or_else(|error| fail(ErrorKind::Io, path, error));
+
+    (ret, is_binary)
+}
+
+fn read(path: &str) -> Document { read_with(path, ParseOptions::default()) }
+
+// Reads `path` in either format, the options only applying to text.
+
+fn read_with(path: &str, options: ParseOptions) -> Document
+{
+    let (file, is_binary) = open(path);
+
+    read_from(file, is_binary, path, options)
+}
+
+fn read_from(
+    mut file: BufReader<File>,
+    is_binary: bool,
+    path: &str,
+    options: ParseOptions
+) -> Document
+{
+    if is_binary {
+        return
+            read_document(file)
+                .unwrap_or_else(|e| fail(ErrorKind::ValueValidation, path, e));
+    }
+
+    let mut text = String::new();
+
+    if let Err(error) = io::Read::read_to_string(&mut file, &mut text) {
+        fail(ErrorKind::Io, path, error);
+    }
+
+    poly::parse_with(&text, options)
+        .unwrap_or_else(|error| fail(ErrorKind::ValueValidation, path, error))
+}
+
+// Inputs are streamed, except text ones written as text, which are read at
+// once before the output is created. Binary outputs keep the frame table of
+// their input, if any. As the output is truncated first, it must not be the
+// input.
+
+fn convert(
+    input: &str,
+    output: &str,
+    to: Option<Format>,
+    frames: bool,
+    options: ParseOptions
+) -> io::Result<()>
+{
+    let is_same =
+        match (fs::canonicalize(input), fs::canonicalize(output)) {
+            (Ok(input), Ok(output)) => input == output,
+            _ => false
+        };
+
+    if is_same {
+        return Err(
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the output would overwrite the input"
+            )
+        );
+    }
+
+    let (file, is_binary) = open(input);
+
+    let to =
+        to.unwrap_or(if is_binary { Format::Text } else { Format::Binary });
+
+    if frames && to == Format::Text {
+        return Err(
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only binary outputs have a frame table"
+            )
+        );
+    }
+
+    if !is_binary {
+        if to == Format::Binary {
+            let out = BufWriter::new(File::create(output)?);
+
+            return binary::convert_text(file, out, frames).map(|_| ());
+        }
+
+        let document = read_from(file, false, input, options);
+        let mut out = BufWriter::new(File::create(output)?);
+
+        return write!(out, "{}", document).and(out.flush());
+    }
+
+    let reader = binary::Reader::new(file)?;
+    let mut out = BufWriter::new(File::create(output)?);
+
+    match to {
+        Format::Binary => {
+            let (header, count) = (reader.header().clone(), reader.len());
+            let frames = frames || reader.has_frames();
+            let mut writer = binary::Writer::new(out, &header, count, frames)?;
+
+            for polygon in reader {
+                let (polygon, attributes) = polygon?;
+
+                writer.write(&polygon, &attributes)?;
+            }
+
+            writer.finish().map(|_| ())
+        },
+
+        Format::Text => {
+            writeln!(out, "{}", reader.header())?;
+
+            for (i, polygon) in reader.enumerate() {
+                let (polygon, attributes) = polygon?;
+
+                let decimals = Decimals::default();
+
+                write!(
+                    out,
+                    "{}",
+                    format_polygon(i, &polygon, &attributes, &decimals)
+                )?;
+            }
+
+            out.flush()
+        }
+    }
+}
+
+// Reads the polygons of `path`, refusing with an error the ones with an edge
+// too short to be a segment, which the algorithms cannot handle.
+
+fn load(path: &str) -> Document
+{
+    let document = read(path);
+
+    for (id, polygon) in document.polygons.iter().enumerate() {
+
+        let error =
+            polygon
+                .segments()
+                .find_map(Result::err)
+                .or_else(|| polygon.frame().err());
+
+        if let Some(error) = error {
+            Args::command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    format!("polygon {}: {} (see `repair`)", id, error)
+                )
+                .exit()
+        }
+    }
+
+    document
+}
+
+fn parse_point(str: &str) -> Option<Point>
+{
+    let mut coordinates =
+        str
+            .split(|c: char| c == ',' || c.is_ascii_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(str::parse::<Unit>);
+
+    match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some(Point { x, y }),
+        _ => None
+    }
+}
+
+fn format_parents(parents: &[isize]) -> String
+{
+    parents.iter().map(|parent| format!("{} ", parent)).collect()
+}
+
+// The attributes of a polygon as the `data-` attributes of an SVG element.
+
+fn to_data_attributes(attributes: &Attributes) -> String
+{
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+
+    attributes
+        .iter()
+        .map(|(key, value)| format!(" data-{}=\"{}\"", key, escape(value)))
+        .collect()
+}
+
+// Gives the corners kept by the repair of the polygon `id` the texts they were
+// read with, the repaired polygons being numbered from `first`.
+
+fn copy_decimals(
+    decimals: &mut Decimals,
+    original: &Decimals,
+    (id, polygon): (usize, &Any),
+    (first, polygons): (usize, &[Any])
+)
+{
+    if original.is_empty() {
+        return;
+    }
+
+    let key = |p: &Point| (p.x.to_bits(), p.y.to_bits());
+
+    let corners =
+        polygon
+            .points()
+            .enumerate()
+            .map(|(i, p)| (key(p), i))
+            .collect::<HashMap<_, _>>();
+
+    for (k, polygon) in polygons.iter().enumerate() {
+        for (j, point) in polygon.points().enumerate() {
+            if let Some(&i) = corners.get(&key(point)) {
+                decimals.copy(original, (id, i), (first + k, j));
+            }
+        }
+    }
+}
+
+fn print_polygons(polygons: &[Any], first: usize)
+{
+    let (attributes, decimals) = (Attributes::default(), Decimals::default());
+
+    for (index, polygon) in polygons.iter().enumerate() {
+        print!(
+            "{}",
+            format_polygon(first + index, polygon, &attributes, &decimals)
+        );
+    }
+}
+
 fn main()
 {
     let args = Args::parse();
+    let registry = Registry::builtin();
+
+    let resolve = |names: &[String]| {
+        names
+            .iter()
+            .map(|name| registry.get(name).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    if args.list_algorithms {
+        for algorithm in registry.iter() {
+            println!("{:<10} {}", algorithm.name(), algorithm.description());
+        }
+
+        return;
+    }
+
+    let Some(command) = args.command else {
+        Args::command()
+            .error(ErrorKind::MissingSubcommand, "a subcommand is required")
+            .exit()
+    };
+
+    match command {
+        Command::Bench {
+            algorithms,
+            corner_count,
+            depth,
+            radius,
+            runs,
+            seed,
+            sizes,
+            csv,
+            svg,
+            paths
+        } => {
+            let algorithms =
+                if algorithms.is_empty() {
+                    registry.iter().collect()
+                } else {
+                    resolve(&algorithms)
+                };
+
+            if runs == 0 {
+                Args::command()
+                    .error(
+                        ErrorKind::ValueValidation,
+                        "at least one run is needed"
+                    )
+                    .exit();
+            }
+
+            let inputs =
+                if paths.is_empty() {
+                    let parameters = Parameters {
+                        attempts: DEFAULT_ATTEMPTS,
+                        corner_count,
+                        dimension: 0.,
+                        family: Family::Star,
+                        nesting: Nesting { branching: 2, depth, fill: 0.5 },
+                        polygon_count: 0,
+                        radius
+                    };
+
+                    match generate_inputs(&parameters, &sizes, seed) {
+                        Ok(inputs) => inputs,
+                        Err(error) => {
+                            Args::command()
+                                .error(ErrorKind::ValueValidation, error)
+                                .exit()
+                        }
+                    }
+                } else {
+                    paths.iter().map(|path| load(path).into_nodes()).collect()
+                };
+
+            let samples = bench(&algorithms, &inputs, runs);
+
+            print!("{}", to_report(&samples));
+
+            if let Some(csv) = csv {
+                fs::write(csv, to_csv(&samples)).unwrap();
+            }
+
+            if let Some(svg) = svg {
+                fs::write(svg, to_chart(&samples)).unwrap();
+            }
+        }
+
+        Command::Compare { algorithms, path, svg } => {
+            if algorithms.len() < 2 {
+                Args::command()
+                    .error(
+                        ErrorKind::TooFewValues,
+                        "at least two algorithms are required"
+                    )
+                    .exit();
+            }
+
+            let nodes = load(&path).into_nodes();
+
+            let Some(disagreement) = compare(&resolve(&algorithms), &nodes)
+            else {
+                println!("all algorithms agree");
+
+                return;
+            };
+
+            print!("{}", disagreement);
+
+            if let Some(svg) = svg {
+                fs::write(svg, disagreement.to_svg()).unwrap();
+            }
+        }
+
+        Command::Convert { frames, keep_decimals, to, input, output } => {
+            let options = ParseOptions { keep_decimals };
+
+            if let Err(error) = convert(&input, &output, to, frames, options) {
+                let paths = format!("{} -> {}", input, output);
+
+                fail(ErrorKind::Io, &paths, error);
+            }
+        }
+
+        Command::Generate { adversarial: true, oracle, .. } => {
+            let mut parents = Vec::new();
+
+            let mut header = Header { version: VERSION, ..Header::default() };
+
+            header.parameters.insert("generator", "generate --adversarial");
+
+            println!("{}", header);
+
+            for case in adversarial() {
+                let first = parents.len();
+
+                parents.extend(
+                    case
+                        .parents
+                        .iter()
+                        .map(|&p| if p == -1 { -1 } else { p + first as isize })
+                );
+
+                println!(
+                    "# {}: expected parents {}",
+                    case.name,
+                    format_parents(&parents[first..]).trim_end()
+                );
+
+                print_polygons(&case.polygons, first);
+            }
+
+            if let Some(oracle) = oracle {
+                fs::write(oracle, format_parents(&parents) + "\n").unwrap();
+            }
+        }
 
-    match args.command {
         Command::Generate {
             corner_count,
             dimension,
             radius,
-            polygon_count
+            polygon_count,
+            seed,
+            depth,
+            branching,
+            fill,
+            oracle,
+            shape,
+            attempts,
+            ..
         } => {
-            let polygons = generate(
+            let seed = seed.unwrap_or_else(rand::random);
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+            let corner_count = corner_count.unwrap();
+            let dimension = dimension.unwrap();
+            let polygon_count = polygon_count.unwrap();
+            let radius = radius.unwrap();
+
+            let parameters = Parameters {
+                attempts,
                 corner_count,
                 dimension,
+                family: shape.into(),
+                nesting: Nesting { branching, depth, fill },
                 polygon_count,
                 radius
-            );
+            };
+
+            let Generation { parents, polygons, report } =
+                match generate(&parameters, &mut rng) {
+                    Ok(generation) => generation,
+                    Err(error) => {
+                        Args::command()
+                            .error(ErrorKind::ValueValidation, error)
+                            .exit()
+                    }
+                };
+
+            if !report.is_complete() {
+                eprintln!("warning: {}", report);
+            }
+
+            let mut generator =
+                format!(
+                    "generate -c {} -d {} -r {} -p {} -s {}",
+                    corner_count,
+                    dimension,
+                    radius,
+                    polygon_count,
+                    seed
+                );
+
+            if depth > 0 {
+                generator += &format!(
+                    " --depth {} --branching {} --fill {}",
+                    depth,
+                    branching,
+                    fill
+                );
+            }
+
+            if shape != Shape::Star {
+                let name = shape.to_possible_value().unwrap();
+
+                generator += &format!(" --shape {}", name.get_name());
+            }
+
+            if attempts != DEFAULT_ATTEMPTS {
+                generator += &format!(" --attempts {}", attempts);
+            }
+
+            let mut header = Header {
+                seed: Some(seed),
+                version: VERSION,
+                ..Header::default()
+            };
+
+            header.parameters.insert("generator", generator);
+
+            println!("{}", header);
+
+            if let Some(oracle) = oracle {
+                fs::write(oracle, format_parents(&parents) + "\n").unwrap();
+            }
+
+            print_polygons(&polygons, 0);
+        }
+
+        Command::Repair { keep_decimals, path } => {
+            let document = read_with(&path, ParseOptions { keep_decimals });
+
+            let mut repaired = Document {
+                header: document.header.clone(),
+                ..Document::default()
+            };
 
-            for (index, polygon) in polygons.into_iter().enumerate() {
-                for point in polygon.points() {
-                    println!("{} {} {}", index, point.x, point.y);
+            let mut first = 0;
+
+            for (id, (polygon, attributes)) in
+                document.polygons.iter().zip(&document.attributes).enumerate()
+            {
+                let repair = polygon.repair();
+
+                for fix in &repair.fixes {
+                    eprintln!("polygon {}: {}", id, fix);
                 }
+
+                let ids =
+                    (first..(first + repair.polygons.len()))
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>();
+
+                if ids != [id.to_string()] {
+                    eprintln!(
+                        "polygon {}: written as [{}]",
+                        id,
+                        ids.join(", ")
+                    );
+                }
+
+                copy_decimals(
+                    &mut repaired.decimals,
+                    &document.decimals,
+                    (id, polygon),
+                    (first, &repair.polygons)
+                );
+
+                first += repair.polygons.len();
+
+                repaired.attributes.extend(
+                    repair.polygons.iter().map(|_| attributes.clone())
+                );
+
+                repaired.polygons.extend(repair.polygons);
             }
+
+            print!("{}", repaired);
         }
 
         Command::Show { path } => {
-            let nodes = parse_from_file(Path::new(path.as_str()));
+            let document = read(&path);
 
             println!("<svg>");
 
-            for node in nodes {
-                println!("\t{}", node.value().1.to_svg());
+            for (polygon, attributes) in
+                document.polygons.iter().zip(&document.attributes)
+            {
+                if attributes.is_empty() {
+                    println!("\t{}", polygon.to_svg());
+                } else {
+                    println!(
+                        "\t<g{}>{}</g>",
+                        to_data_attributes(attributes),
+                        polygon.to_svg()
+                    );
+                }
             }
 
             println!("</svg>");
         },
 
-        Command::Process { algorithm, path } => {
-            let nodes = parse_from_file(Path::new(path.as_str()));
+        Command::Locate { algorithm, path, points } => {
+            let nodes = load(&path).into_nodes();
+            let root = registry.get(&algorithm).unwrap().build(&nodes);
 
-            let _root =
-                match algorithm {
-                    #[cfg(feature = "frames")]
-                    Algorithm::Frames => process_frames(&nodes),
+            let points =
+                if points.is_empty() {
+                    stdin()
+                        .lines()
+                        .map(Result::unwrap)
+                        .filter(|x| !x.trim().is_empty())
+                        .collect()
+                } else {
+                    points
+                };
 
-                    #[cfg(feature = "naive")]
-                    Algorithm::Naive => process_naive(&nodes)
+            for point in points {
+                let Some(point) = parse_point(&point) else {
+                    Args::command()
+                        .error(
+                            ErrorKind::ValueValidation,
+                            format!("invalid point: `{}`", point)
+                        )
+                        .exit()
                 };
 
-            for node in nodes {
-                print!("{} ", node.parent().unwrap().value().0);
+                print!("{} ", locate(&root, &point).unwrap_or(-1));
+            }
+
+            println!();
+        }
+
+        Command::Process {
+            algorithm,
+            annotate,
+            dag,
+            duplicates,
+            overlaps,
+            stats,
+            tolerance,
+            path
+        } => {
+            let document = load(&path);
+            let attributes = document.attributes.clone();
+            let nodes = document.into_nodes();
+
+            if overlaps {
+                for (a, b) in find_overlaps(&nodes) {
+                    eprintln!("warning: polygons {} and {} overlap", a, b);
+                }
+            }
+
+            if let Some(dag) = dag {
+                let lines =
+                    containment_dag(&nodes)
+                        .iter()
+                        .map(
+                            |parents| match parents.is_empty() {
+                                true => format_parents(&[-1]),
+                                false => format_parents(parents)
+                            }
+                        )
+                        .collect::<Vec<_>>();
+
+                fs::write(dag, lines.join("\n") + "\n").unwrap();
+            }
+
+            let algorithm = registry.get(&algorithm).unwrap();
+
+            // Only the policy makes the parents of duplicate or coincident
+            // polygons independent of the input order.
+
+            let result =
+                match duplicates {
+                    Some(duplicates) => {
+                        process_with_policy(
+                            algorithm,
+                            &nodes,
+                            duplicates.into(),
+                            tolerance
+                        )
+                    },
+
+                    None => Ok(algorithm.build_with_stats(&nodes))
+                };
+
+            let (root, process_stats) =
+                match result {
+                    Ok(result) => result,
+                    Err(error) => {
+                        Args::command()
+                            .error(ErrorKind::ValueValidation, error)
+                            .exit()
+                    }
+                };
+
+            for id in 1..root.len() {
+                let parent = root.value(root.parent(id).unwrap()).0;
+
+                // The computed parent replaces any `parent` attribute of the
+                // polygon, keys appearing once.
+
+                if annotate {
+                    let mut line = Attributes::default();
+
+                    line.insert("parent", parent.to_string());
+
+                    for (key, value) in attributes[id - 1].iter() {
+                        if key != "parent" {
+                            line.insert(key, value);
+                        }
+                    }
+
+                    println!("@{} {}", id - 1, line);
+                } else {
+                    print!("{} ", parent);
+                }
+            }
+
+            if !annotate {
+                println!();
+            }
+
+            if stats {
+                println!("{}", process_stats);
             }
         }
     }
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "frames", feature = "naive", feature = "quadtree"))]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_locate_negative_points()
+    {
+        let registry = Registry::builtin();
+        let algorithm = registry.iter().next().unwrap().name();
+
+        let args =
+            ["polygon", "locate", "-a", algorithm, "t.poly", "-1,1", "-15,-15"];
 
-    println!();
+        let Some(Command::Locate { path, points, .. }) =
+            Args::try_parse_from(args).unwrap().command
+        else {
+            panic!("expected the `locate` subcommand");
+        };
+
+        assert_eq!(path, "t.poly");
+
+        assert_eq!(
+            points.iter().map(|point| parse_point(point)).collect::<Vec<_>>(),
+            [Some(Point { x: -1., y: 1. }), Some(Point { x: -15., y: -15. })]
+        );
+    }
 }
@@ -0,0 +1,1045 @@
+pub mod binary;
+
+use super::geo::polygon::Any;
+use super::geo::Unit;
+use super::tree::Node;
+use super::IndexedNodes;
+
+use derive_more::Display;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+// The version written in the header, and the highest one which is read. Files
+// without a header are of version 1.
+
+pub const VERSION: u32 = 2;
+
+const MAGIC: &str = "#!poly";
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// Ordered `key=value` pairs, each key appearing once. Values holding blanks,
+// quotes or backslashes are written between double quotes.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Attributes { pairs: Vec<(String, String)> }
+
+// The original text of coordinates, by polygon, corner and axis (0 for x and
+// 1 for y), for the ones written differently by default. A text is only used
+// while the coordinate at its position keeps the value it was read as.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Decimals { texts: HashMap<(usize, usize, usize), String> }
+
+// The contents of a `.poly` file: its header, and each polygon along with its
+// attributes, in the order of the file. Polygons are numbered by that order,
+// whatever their ids in the file.
+//
+// A file of version 2 is laid out as follows, blank lines and everything from
+// a `#` starting a field to the end of the line being ignored:
+//
+//     #!poly 2 units=mm seed=42 generator="generate -c 4"
+//     @0 name=outer layer=walls
+//     0 1 1
+//     0 5 1
+//     0 5 5
+//
+// The header line is optional but must come first. An `@id` line gives
+// attributes to the polygon `id` and may appear anywhere in the file.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document
+{
+    pub attributes: Vec<Attributes>,
+    pub decimals: Decimals,
+    pub header: Header,
+    pub polygons: Vec<Any>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Header
+{
+    pub parameters: Attributes,
+    pub seed: Option<u64>,
+    pub units: Option<String>,
+    pub version: u32
+}
+
+// `keep_decimals` fills the decimals of the document, so that coordinates are
+// written back with the text they were read from.
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseOptions { pub keep_decimals: bool }
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Display)]
+#[display(fmt = "line {}: {}", line, kind)]
+pub struct ParseError { pub kind: ParseErrorKind, pub line: usize }
+
+// Reads the polygons of a text file one at a time, line by line. As `@id` lines
+// may come anywhere in the file, the attributes are only known through
+// `finish` once every polygon has been read. Parse errors are given as
+// `InvalidData` errors.
+
+pub struct TextReader<R>
+{
+    count: usize,
+    current: Option<(usize, Any)>,
+    header: Header,
+    index_of: HashMap<usize, usize>,
+    is_first: bool,
+    lines: Option<io::Lines<R>>,
+    number: usize,
+    pending: Vec<(usize, usize, Attributes)>
+}
+
+// A line which is not blank, a header only being one on the first such line.
+
+enum Line<'a>
+{
+    Attributes(usize, Attributes),
+    Comment,
+    Corner { id: usize, x: (Unit, &'a str), y: (Unit, &'a str) },
+    Header(Header)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Display)]
+pub enum ParseErrorKind
+{
+    #[display(fmt = "invalid attribute `{}`: expected key=value", _0)]
+    InvalidAttribute(String),
+
+    #[display(fmt = "invalid coordinate `{}`", _0)]
+    InvalidCoordinate(String),
+
+    #[display(fmt = "invalid polygon id `{}`", _0)]
+    InvalidId(String),
+
+    #[display(fmt = "invalid seed `{}`", _0)]
+    InvalidSeed(String),
+
+    #[display(fmt = "invalid version `{}`", _0)]
+    InvalidVersion(String),
+
+    #[display(fmt = "missing version in header")]
+    MissingVersion,
+
+    #[display(fmt = "attributes of polygon {} which has no corner", _0)]
+    UnknownPolygon(usize),
+
+    #[display(fmt = "unsupported version {}: at most {} is known", _0, VERSION)]
+    UnsupportedVersion(u32),
+
+    #[display(fmt = "unterminated quote")]
+    UnterminatedQuote,
+
+    #[display(fmt = "expected `id x y`, found {} fields", _0)]
+    WrongFieldCount(usize)
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Attributes {
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    // Replaces the value of `key` if it is already there.
+
+    pub fn insert<K, V>(&mut self, key: K, value: V)
+        where K: Into<String>, V: Into<String>
+    {
+        let (key, value) = (key.into(), value.into());
+
+        match self.pairs.iter_mut().find(|(other, _)| *other == key) {
+            Some(pair) => pair.1 = value,
+            None => self.pairs.push((key, value))
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn get(&self, key: &str) -> Option<&str>
+    {
+        self
+            .pairs
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool { self.pairs.is_empty() }
+    pub fn layer(&self) -> Option<&str> { self.get("layer") }
+    pub fn len(&self) -> usize { self.pairs.len() }
+    pub fn name(&self) -> Option<&str> { self.get("name") }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)>
+    {
+        self.pairs.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+impl Decimals {
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    pub fn insert(
+        &mut self,
+        polygon: usize,
+        corner: usize,
+        axis: usize,
+        text: &str,
+        value: Unit
+    )
+    {
+        if text != format_unit(value) {
+            self.texts.insert((polygon, corner, axis), text.into());
+        }
+    }
+
+    // Gives the corner `to` the texts of the corner `from` of `other`, for
+    // corners which moved.
+
+    pub fn copy(
+        &mut self,
+        other: &Decimals,
+        from: (usize, usize),
+        to: (usize, usize)
+    )
+    {
+        for axis in 0..2 {
+            if let Some(text) = other.texts.get(&(from.0, from.1, axis)) {
+                self.texts.insert((to.0, to.1, axis), text.clone());
+            }
+        }
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn is_empty(&self) -> bool { self.texts.is_empty() }
+    pub fn len(&self) -> usize { self.texts.len() }
+
+    /*********/
+    /* UTILS */
+    /*********/
+
+    // The original text of the coordinate if it still has the same value, and
+    // else the shortest text which parses back to `value`.
+
+    pub fn format(
+        &self,
+        polygon: usize,
+        corner: usize,
+        axis: usize,
+        value: Unit
+    ) -> String
+    {
+        let text =
+            self
+                .texts
+                .get(&(polygon, corner, axis))
+                .filter(|text| is_text_of(text, value));
+
+        match text {
+            Some(text) => text.clone(),
+            None => format_unit(value)
+        }
+    }
+}
+
+impl Document {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(polygons: Vec<Any>) -> Self
+    {
+        Self {
+            attributes: vec! [Attributes::default(); polygons.len()],
+            decimals: Decimals::default(),
+            header: Header::default(),
+            polygons
+        }
+    }
+
+    /***************/
+    /* CONVERSIONS */
+    /***************/
+
+    pub fn into_nodes(self) -> IndexedNodes
+    {
+        to_nodes(self.polygons.into_iter())
+    }
+
+    pub fn nodes(&self) -> IndexedNodes
+    {
+        to_nodes(self.polygons.iter().cloned())
+    }
+}
+
+impl<R> TextReader<R> where R: BufRead {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(inner: R) -> Self
+    {
+        Self {
+            count: 0,
+            current: None,
+            header: Header::default(),
+            index_of: HashMap::new(),
+            is_first: true,
+            lines: Some(inner.lines()),
+            number: 0,
+            pending: Vec::new()
+        }
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    // The header and the attributes of each polygon read.
+
+    pub fn finish(self) -> Result<(Header, Vec<Attributes>), ParseError>
+    {
+        let attributes =
+            resolve_attributes(self.pending, &self.index_of, self.count)?;
+
+        Ok((self.header, attributes))
+    }
+
+    fn fail(&mut self, kind: ParseErrorKind) -> io::Error
+    {
+        self.lines = None;
+
+        let error = ParseError { kind, line: self.number };
+
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl<R> Iterator for TextReader<R> where R: BufRead {
+    type Item = io::Result<Any>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        while let Some(line) = self.lines.as_mut()?.next() {
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => {
+                    self.lines = None;
+
+                    return Some(Err(error));
+                }
+            };
+
+            self.number += 1;
+
+            let line = match parse_line(&line, self.is_first) {
+                Ok(Some(line)) => line,
+                Ok(None) => continue,
+                Err(kind) => return Some(Err(self.fail(kind)))
+            };
+
+            self.is_first = false;
+
+            match line {
+                Line::Attributes(id, attributes) => {
+                    self.pending.push((self.number, id, attributes));
+                },
+
+                Line::Comment => (),
+
+                Line::Corner { id, x: (x, _), y: (y, _) } => {
+                    let point = (x, y).into();
+
+                    if let Some((current, polygon)) = &mut self.current {
+                        if *current == id {
+                            polygon.points.push(point);
+
+                            continue;
+                        }
+                    }
+
+                    self.index_of.entry(id).or_insert(self.count);
+                    self.count += 1;
+
+                    let polygon = Any { points: vec! [point] };
+                    let ret = self.current.replace((id, polygon));
+
+                    if let Some((_, ret)) = ret {
+                        return Some(Ok(ret));
+                    }
+                },
+
+                Line::Header(header) => self.header = header
+            }
+        }
+
+        self.lines = None;
+        self.current.take().map(|(_, polygon)| Ok(polygon))
+    }
+}
+
+impl Default for Header {
+    fn default() -> Self
+    {
+        Self {
+            parameters: Attributes::default(),
+            seed: None,
+            units: None,
+            version: 1
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/*************/
+/* `Display` */
+/*************/
+
+impl fmt::Display for Attributes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        for (i, (key, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}=", key)?;
+            write_value(f, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        writeln!(f, "{}", self.header)?;
+
+        let empty = Attributes::default();
+
+        for (i, polygon) in self.polygons.iter().enumerate() {
+            let attributes = self.attributes.get(i).unwrap_or(&empty);
+
+            write!(
+                f,
+                "{}",
+                format_polygon(i, polygon, attributes, &self.decimals)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Always written with the current version, which reads all the older ones.
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} {}", MAGIC, VERSION)?;
+
+        if let Some(units) = &self.units {
+            write!(f, " units=")?;
+            write_value(f, units)?;
+        }
+
+        if let Some(seed) = self.seed {
+            write!(f, " seed={}", seed)?;
+        }
+
+        if !self.parameters.is_empty() {
+            write!(f, " {}", self.parameters)?;
+        }
+
+        Ok(())
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// The lines of a polygon in the text format, for writing files one polygon at
+// a time.
+
+pub fn format_polygon(
+    id: usize,
+    polygon: &Any,
+    attributes: &Attributes,
+    decimals: &Decimals
+) -> String
+{
+    let mut ret = String::new();
+
+    if !attributes.is_empty() {
+        ret += &format!("@{} {}\n", id, attributes);
+    }
+
+    for (i, point) in polygon.points().enumerate() {
+        ret +=
+            &format!(
+                "{} {} {}\n",
+                id,
+                decimals.format(id, i, 0, point.x),
+                decimals.format(id, i, 1, point.y)
+            );
+    }
+
+    ret
+}
+
+// The shortest digits parsing back to `value`. As `{}` never uses exponents,
+// they are used past the same bounds as in JavaScript, where the digits would
+// otherwise be drowned in zeros.
+
+pub fn format_unit(value: Unit) -> String
+{
+    let magnitude = value.abs();
+
+    if magnitude >= 1e21 || (magnitude < 1e-6 && magnitude != 0.) {
+        format!("{:e}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn parse(str: &str) -> Result<Document, ParseError>
+{
+    parse_with(str, ParseOptions::default())
+}
+
+pub fn parse_with(str: &str, options: ParseOptions)
+    -> Result<Document, ParseError>
+{
+    let mut ret = Document::default();
+
+    let mut current = None;
+    let mut index_of = HashMap::new();
+    let mut pending = Vec::new();
+    let mut is_first = true;
+
+    for (number, line) in str.lines().enumerate() {
+        let error = |kind| ParseError { kind, line: number + 1 };
+
+        let Some(line) = parse_line(line, is_first).map_err(error)? else {
+            continue;
+        };
+
+        is_first = false;
+
+        match line {
+            Line::Attributes(id, attributes) => {
+                pending.push((number + 1, id, attributes));
+            },
+
+            Line::Comment => (),
+
+            Line::Corner { id, x: (x, x_text), y: (y, y_text) } => {
+                if current != Some(id) {
+                    current = Some(id);
+                    index_of.entry(id).or_insert(ret.polygons.len());
+                    ret.polygons.push(Any::default());
+                }
+
+                if options.keep_decimals {
+                    let polygon = ret.polygons.len() - 1;
+                    let corner = ret.polygons[polygon].points.len();
+
+                    ret.decimals.insert(polygon, corner, 0, x_text, x);
+                    ret.decimals.insert(polygon, corner, 1, y_text, y);
+                }
+
+                ret.polygons.last_mut().unwrap().points.push((x, y).into());
+            },
+
+            Line::Header(header) => ret.header = header
+        }
+    }
+
+    ret.attributes =
+        resolve_attributes(pending, &index_of, ret.polygons.len())?;
+
+    Ok(ret)
+}
+
+fn is_text_of(text: &str, value: Unit) -> bool
+{
+    text.parse::<Unit>().is_ok_and(|parsed| parsed.to_bits() == value.to_bits())
+}
+
+fn is_header(line: &str) -> bool
+{
+    line
+        .strip_prefix(MAGIC)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+fn parse_attributes(str: &str) -> Result<(usize, Attributes), ParseErrorKind>
+{
+    let mut tokens = tokenize(str)?.into_iter();
+
+    let id = parse_id(&tokens.next().unwrap_or_default())?;
+
+    Ok((id, parse_pairs(tokens)?))
+}
+
+fn parse_coordinate(str: &str) -> Result<Unit, ParseErrorKind>
+{
+    str
+        .parse()
+        .map_err(|_| ParseErrorKind::InvalidCoordinate(str.to_owned()))
+}
+
+fn parse_header(str: &str) -> Result<Header, ParseErrorKind>
+{
+    let mut tokens = tokenize(str)?.into_iter();
+
+    let version = tokens.next().ok_or(ParseErrorKind::MissingVersion)?;
+
+    let version =
+        version
+            .parse::<u32>()
+            .map_err(|_| ParseErrorKind::InvalidVersion(version.clone()))?;
+
+    if version == 0 || version > VERSION {
+        return Err(ParseErrorKind::UnsupportedVersion(version));
+    }
+
+    let mut ret = Header { version, ..Header::default() };
+
+    for (key, value) in parse_pairs(tokens)?.pairs {
+        match key.as_str() {
+            "seed" => {
+                let seed =
+                    value
+                        .parse()
+                        .map_err(|_| ParseErrorKind::InvalidSeed(value))?;
+
+                ret.seed = Some(seed);
+            },
+
+            "units" => ret.units = Some(value),
+
+            _ => ret.parameters.insert(key, value)
+        }
+    }
+
+    Ok(ret)
+}
+
+fn parse_id(str: &str) -> Result<usize, ParseErrorKind>
+{
+    str.parse().map_err(|_| ParseErrorKind::InvalidId(str.to_owned()))
+}
+
+fn parse_line(line: &str, is_first: bool)
+    -> Result<Option<Line<'_>>, ParseErrorKind>
+{
+    let line = line.trim();
+
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    if is_first && is_header(line) {
+        return Ok(Some(Line::Header(parse_header(&line[MAGIC.len()..])?)));
+    }
+
+    if line.starts_with('#') {
+        return Ok(Some(Line::Comment));
+    }
+
+    if let Some(rest) = line.strip_prefix('@') {
+        let (id, attributes) = parse_attributes(rest)?;
+
+        return Ok(Some(Line::Attributes(id, attributes)));
+    }
+
+    let fields =
+        line
+            .split('#')
+            .next()
+            .unwrap()
+            .split_ascii_whitespace()
+            .collect::<Vec<_>>();
+
+    let &[id, x_text, y_text] = fields.as_slice() else {
+        return Err(ParseErrorKind::WrongFieldCount(fields.len()));
+    };
+
+    let id = parse_id(id)?;
+    let x = (parse_coordinate(x_text)?, x_text);
+    let y = (parse_coordinate(y_text)?, y_text);
+
+    Ok(Some(Line::Corner { id, x, y }))
+}
+
+// Keys are made of ASCII letters, digits, `_`, `-` and `.`.
+
+fn parse_pairs<I>(tokens: I) -> Result<Attributes, ParseErrorKind>
+    where I: Iterator<Item = String>
+{
+    let mut ret = Attributes::default();
+
+    for token in tokens {
+        let is_key = |key: &str| {
+            !key.is_empty() &&
+                key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+        };
+
+        match token.split_once('=') {
+            Some((key, value)) if is_key(key) => ret.insert(key, value),
+            _ => return Err(ParseErrorKind::InvalidAttribute(token))
+        }
+    }
+
+    Ok(ret)
+}
+
+// The attributes of each of the `count` polygons, from the `@id` lines given by
+// line number, the polygon `id` being the first one with that id.
+
+fn resolve_attributes(
+    pending: Vec<(usize, usize, Attributes)>,
+    index_of: &HashMap<usize, usize>,
+    count: usize
+) -> Result<Vec<Attributes>, ParseError>
+{
+    let mut ret = vec! [Attributes::default(); count];
+
+    for (line, id, attributes) in pending {
+        let Some(&index) = index_of.get(&id) else {
+            let kind = ParseErrorKind::UnknownPolygon(id);
+
+            return Err(ParseError { kind, line });
+        };
+
+        for (key, value) in attributes.pairs {
+            ret[index].insert(key, value);
+        }
+    }
+
+    Ok(ret)
+}
+
+// Splits `str` on blanks outside of double quotes, which are removed, up to a
+// field starting with `#`. Within quotes, a backslash escapes the next char.
+
+fn tokenize(str: &str) -> Result<Vec<String>, ParseErrorKind>
+{
+    let mut ret = Vec::new();
+    let mut chars = str.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        if matches!(chars.peek(), None | Some('#')) {
+            return Ok(ret);
+        }
+
+        let mut token = String::new();
+        let mut is_quoted = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => is_quoted = !is_quoted,
+
+                '\\' if is_quoted => {
+                    let c =
+                        chars.next().ok_or(ParseErrorKind::UnterminatedQuote)?;
+
+                    token.push(c);
+                },
+
+                c if c.is_whitespace() && !is_quoted => break,
+
+                c => token.push(c)
+            }
+        }
+
+        if is_quoted {
+            return Err(ParseErrorKind::UnterminatedQuote);
+        }
+
+        ret.push(token);
+    }
+}
+
+fn to_nodes<I>(polygons: I) -> IndexedNodes where I: Iterator<Item = Any>
+{
+    polygons
+        .enumerate()
+        .map(|(i, polygon)| Node::new((i as isize, polygon)))
+        .collect()
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result
+{
+    let is_plain =
+        !value.is_empty() &&
+            !value.starts_with('#') &&
+            !value.contains(|c: char| c.is_whitespace() || "\"\\".contains(c));
+
+    if is_plain {
+        return write!(f, "{}", value);
+    }
+
+    write!(f, "\"")?;
+
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            write!(f, "\\")?;
+        }
+
+        write!(f, "{}", c)?;
+    }
+
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use super::super::geo::Point;
+
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_v2()
+    {
+        let testing = indoc! {r#"
+            #!poly 2 units=mm seed=42 generator="generate -c 3" fill=0.5
+
+            # The outer polygon.
+            @7 name="outer wall" layer=walls
+            7 0 0
+            7 6 0   # a trailing comment
+            7 6 6
+            3 1 1
+            3 2 1
+            3 1 2
+            @3 note="say \"hi\"" layer=holes
+            @7 layer=outside
+        "#};
+
+        let testing = parse(testing).unwrap();
+
+        assert_eq!(testing.header.version, 2);
+        assert_eq!(testing.header.units.as_deref(), Some("mm"));
+        assert_eq!(testing.header.seed, Some(42));
+        assert_eq!(
+            testing.header.parameters.get("generator"),
+            Some("generate -c 3")
+        );
+        assert_eq!(testing.header.parameters.get("fill"), Some("0.5"));
+
+        assert_eq!(testing.polygons.len(), 2);
+        assert_eq!(testing.polygons[0].points[1], Point { x: 6., y: 0. });
+        assert_eq!(testing.polygons[1].points.len(), 3);
+
+        assert_eq!(testing.attributes[0].name(), Some("outer wall"));
+        assert_eq!(testing.attributes[0].layer(), Some("outside"));
+        assert_eq!(testing.attributes[0].len(), 2);
+        assert_eq!(testing.attributes[1].get("note"), Some("say \"hi\""));
+        assert_eq!(testing.attributes[1].layer(), Some("holes"));
+    }
+
+    #[test]
+    fn test_parse_v1()
+    {
+        let testing = parse("# generate --seed 42\n0 1 1\n0 2 1\n0 1 2\n");
+        let testing = testing.unwrap();
+
+        assert_eq!(testing.header, Header::default());
+        assert_eq!(testing.polygons.len(), 1);
+        assert_eq!(testing.attributes, vec! [Attributes::default()]);
+    }
+
+    #[test]
+    fn test_round_trip()
+    {
+        let mut expected = Document::new(
+            vec! [
+                Any { points: vec! [(0.5, 0.).into(), (1e-7, 3.).into()] },
+                Any { points: vec! [(-2., 1.25).into()] }
+            ]
+        );
+
+        expected.header.units = Some("light years".to_owned());
+        expected.header.seed = Some(7);
+        expected.header.parameters.insert("empty", "");
+        expected.attributes[1].insert("name", "#1 \\ \"b\"");
+        expected.attributes[1].insert("layer", "top");
+
+        let text = expected.to_string();
+
+        expected.header.version = VERSION;
+
+        assert_eq!(parse(&text).unwrap(), expected);
+        assert!(text.starts_with("#!poly 2 units=\"light years\" seed=7"));
+    }
+
+    #[test]
+    fn test_format_unit()
+    {
+        let cases = [
+            (0.1, "0.1"),
+            (1., "1"),
+            (1000., "1000"),
+            (1e-6, "0.000001"),
+            (1e-7, "1e-7"),
+            (-0., "-0"),
+            (1e21, "1e21"),
+            (1e-300, "1e-300"),
+            (0.1 + 0.2, "0.30000000000000004")
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(format_unit(value), expected);
+            assert_eq!(expected.parse::<Unit>().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_write_is_idempotent()
+    {
+        let text = "0 0.1 1e-300\n0 1.50 2\n0 1e3 -0.0\n";
+
+        for keep_decimals in [false, true] {
+            let options = ParseOptions { keep_decimals };
+
+            let once = parse_with(text, options).unwrap().to_string();
+            let twice = parse_with(&once, options).unwrap().to_string();
+
+            assert_eq!(once, twice);
+        }
+
+        let testing = parse(text).unwrap().to_string();
+
+        assert!(testing.ends_with("0 0.1 1e-300\n0 1.5 2\n0 1000 -0\n"));
+    }
+
+    #[test]
+    fn test_keep_decimals()
+    {
+        // The same values are spelled in two ways.
+
+        let text = "0 0.10 1\n0 1.30 1.0\n0 1.3 0.10\n1 1.0 1.30\n";
+        let options = ParseOptions { keep_decimals: true };
+
+        let mut testing = parse_with(text, options).unwrap();
+
+        assert_eq!(testing.decimals.len(), 6);
+        assert!(testing.to_string().ends_with(text));
+
+        // Changed coordinates are written in their shortest form.
+
+        testing.polygons[0].points[1].y = 0.1 + 0.2;
+
+        let expected = "0 1.30 0.30000000000000004\n0 1.3 0.10\n1 1.0 1.30\n";
+
+        assert!(testing.to_string().ends_with(expected));
+
+        assert!(parse(text).unwrap().decimals.is_empty());
+    }
+
+    // The reader gives the same polygons, header and attributes as `parse`.
+
+    #[test]
+    fn test_text_reader()
+    {
+        let text = indoc! {r#"
+            #!poly 2 units=mm
+            @3 layer=holes
+            7 0 0
+            7 6 0
+            3 1 1
+            3 2 1
+            7 6 6
+            @7 name=outer
+        "#};
+
+        let expected = parse(text).unwrap();
+        let mut reader = TextReader::new(text.as_bytes());
+
+        let polygons =
+            (&mut reader).collect::<io::Result<Vec<_>>>().unwrap();
+
+        let (header, attributes) = reader.finish().unwrap();
+
+        assert_eq!(polygons, expected.polygons);
+        assert_eq!(polygons.len(), 3);
+        assert_eq!(header, expected.header);
+        assert_eq!(attributes, expected.attributes);
+
+        // An error ends the polygons, and is given with its line.
+
+        let mut reader = TextReader::new(&b"0 0 0
+0 1 0
+1 x 0
+2 0 0
+"[..]);
+
+        let error = reader.next().unwrap().unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "line 3: invalid coordinate `x`");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_errors()
+    {
+        let cases = [
+            ("0 1\n", 1, ParseErrorKind::WrongFieldCount(2)),
+            ("0 1 x\n", 1, ParseErrorKind::InvalidCoordinate("x".into())),
+            ("0 0 0\n-1 0 0\n", 2, ParseErrorKind::InvalidId("-1".into())),
+            ("#!poly\n", 1, ParseErrorKind::MissingVersion),
+            ("#!poly 3\n", 1, ParseErrorKind::UnsupportedVersion(3)),
+            ("#!poly 2 seed=x\n", 1, ParseErrorKind::InvalidSeed("x".into())),
+            ("@0 a\n", 1, ParseErrorKind::InvalidAttribute("a".into())),
+            ("@0 name=\"a\n", 1, ParseErrorKind::UnterminatedQuote),
+            ("@1 name=a\n0 0 0\n", 1, ParseErrorKind::UnknownPolygon(1))
+        ];
+
+        for (text, line, kind) in cases {
+            assert_eq!(parse(text), Err(ParseError { kind, line }), "{}", text);
+        }
+
+        // The header is only recognized on the first line.
+
+        assert_eq!(parse("0 0 0\n#!poly 3\n").unwrap().polygons.len(), 1);
+    }
+}
@@ -0,0 +1,569 @@
+use super::super::geo::polygon::{Any, Polygon, Rectangle};
+use super::super::geo::{Point, Unit};
+use super::{parse_header, parse_pairs, tokenize, Attributes, Document, Header};
+use super::{TextReader, MAGIC as TEXT_MAGIC};
+
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+/*************/
+/* CONSTANTS */
+/*************/
+
+// The first bytes of a binary file, which no text file starts with.
+
+pub const MAGIC: &[u8; 8] = b"POLYBIN\0";
+
+pub const VERSION: u32 = 1;
+
+const FLAG_FRAMES: u32 = 1;
+
+// The size of an entry of the frame table: two corners of two `f64`.
+
+const FRAME_SIZE: u64 = 32;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+// Reads the polygons of a binary file one at a time, along with their
+// attributes. All the numbers of the format are little-endian:
+//
+//     magic       8 bytes, `MAGIC`
+//     version     u32
+//     flags       u32, bit 0 telling whether there is a frame table
+//     count       u64, the number of polygons
+//     header      u32 length, then the text header line in UTF-8
+//     polygons    count times:
+//                     u32 length, then the attributes in UTF-8
+//                     u32 corner count, then the corners as `f64` pairs
+//     frames      if flagged, count times the bottom left and top right
+//                 corners of the frame of the polygon, as `f64` pairs
+//
+// Attributes and the header are written as in the text format.
+
+pub struct Reader<R>
+{
+    count: u64,
+    has_frames: bool,
+    header: Header,
+    inner: R,
+    remaining: u64
+}
+
+// Writes polygons one at a time. Their count is given first, and the frame
+// table, when there is one, is written by `finish`.
+
+pub struct Writer<W>
+{
+    count: u64,
+    frames: Option<Vec<Rectangle>>,
+    inner: W,
+    written: u64
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl<R> Reader<R> where R: Read {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(mut inner: R) -> io::Result<Self>
+    {
+        let (count, has_frames) = read_prefix(&mut inner)?;
+
+        let text = read_string(&mut inner)?;
+
+        let header =
+            match text.strip_prefix(TEXT_MAGIC) {
+                Some(rest) => parse_header(rest).map_err(invalid_data)?,
+                None if text.is_empty() => Header::default(),
+                None => return Err(invalid_data("invalid header"))
+            };
+
+        Ok(Self { count, has_frames, header, inner, remaining: count })
+    }
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn has_frames(&self) -> bool { self.has_frames }
+    pub fn header(&self) -> &Header { &self.header }
+    pub fn len(&self) -> u64 { self.count }
+    pub fn is_empty(&self) -> bool { self.count == 0 }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    fn read_polygon(&mut self) -> io::Result<(Any, Attributes)>
+    {
+        let text = read_string(&mut self.inner)?;
+
+        let attributes =
+            tokenize(&text)
+                .and_then(|tokens| parse_pairs(tokens.into_iter()))
+                .map_err(invalid_data)?;
+
+        let corner_count = read_u32(&mut self.inner)? as usize;
+
+        // The count is not trusted with more memory than it is backed by.
+
+        let mut points = Vec::with_capacity(corner_count.min(1 << 16));
+
+        for _ in 0..corner_count {
+            points.push(read_point(&mut self.inner)?);
+        }
+
+        Ok((Any { points }, attributes))
+    }
+}
+
+impl<R> Iterator for Reader<R> where R: Read {
+    type Item = io::Result<(Any, Attributes)>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        let ret = self.read_polygon();
+
+        if ret.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(ret)
+    }
+}
+
+impl<W> Writer<W> where W: Write {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new(mut inner: W, header: &Header, count: u64, frames: bool)
+        -> io::Result<Self>
+    {
+        let flags = if frames { FLAG_FRAMES } else { 0 };
+
+        inner.write_all(MAGIC)?;
+        inner.write_all(&VERSION.to_le_bytes())?;
+        inner.write_all(&flags.to_le_bytes())?;
+        inner.write_all(&count.to_le_bytes())?;
+
+        write_string(&mut inner, &header.to_string())?;
+
+        let frames = frames.then(Vec::new);
+
+        Ok(Self { count, frames, inner, written: 0 })
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    // Fails on polygons whose frame is degenerate when frames are written.
+
+    pub fn write(&mut self, polygon: &Any, attributes: &Attributes)
+        -> io::Result<()>
+    {
+        if self.written == self.count {
+            return Err(invalid_input("more polygons than announced"));
+        }
+
+        if let Some(frames) = &mut self.frames {
+            frames.push(polygon.frame().map_err(invalid_input)?);
+        }
+
+        let corner_count =
+            u32::try_from(polygon.points.len())
+                .map_err(|_| invalid_input("too many corners"))?;
+
+        write_string(&mut self.inner, &attributes.to_string())?;
+
+        self.inner.write_all(&corner_count.to_le_bytes())?;
+
+        for point in &polygon.points {
+            write_point(&mut self.inner, point)?;
+        }
+
+        self.written += 1;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W>
+    {
+        if self.written != self.count {
+            return Err(invalid_input("fewer polygons than announced"));
+        }
+
+        for frame in self.frames.iter().flatten() {
+            write_point(&mut self.inner, &frame.bottom_left())?;
+            write_point(&mut self.inner, &frame.top_right())?;
+        }
+
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// Writes the text file `input` in the binary format with at most one polygon
+// in memory, by reading it twice: once for its header, the count of its
+// polygons and their attributes, and once for their corners.
+
+pub fn convert_text<R, W>(mut input: R, output: W, frames: bool)
+    -> io::Result<W>
+    where R: BufRead + Seek, W: Write
+{
+    let mut reader = TextReader::new(&mut input);
+    let mut count = 0;
+
+    for polygon in &mut reader {
+        polygon?;
+        count += 1;
+    }
+
+    let (header, attributes) = reader.finish().map_err(invalid_data)?;
+
+    input.rewind()?;
+
+    let mut writer = Writer::new(output, &header, count, frames)?;
+
+    for (polygon, attributes) in TextReader::new(input).zip(&attributes) {
+        writer.write(&polygon?, attributes)?;
+    }
+
+    writer.finish()
+}
+
+pub fn is_binary(bytes: &[u8]) -> bool { bytes.starts_with(MAGIC) }
+
+pub fn read_document<R>(inner: R) -> io::Result<Document> where R: Read
+{
+    let reader = Reader::new(inner)?;
+
+    let mut ret = Document {
+        header: reader.header().clone(),
+        ..Document::default()
+    };
+
+    for polygon in reader {
+        let (polygon, attributes) = polygon?;
+
+        ret.attributes.push(attributes);
+        ret.polygons.push(polygon);
+    }
+
+    Ok(ret)
+}
+
+// Only reads the frame table, seeking over the polygons. Gives `None` when
+// the file has no frame table.
+
+pub fn read_frames<R>(mut inner: R) -> io::Result<Option<Vec<Rectangle>>>
+    where R: Read + Seek
+{
+    let (count, has_frames) = read_prefix(&mut inner)?;
+
+    if !has_frames {
+        return Ok(None);
+    }
+
+    let size =
+        count
+            .checked_mul(FRAME_SIZE)
+            .and_then(|size| i64::try_from(size).ok())
+            .ok_or_else(|| invalid_data("too many polygons"))?;
+
+    inner.seek(SeekFrom::End(-size))?;
+
+    let mut ret = Vec::new();
+
+    for _ in 0..count {
+        let bottom_left = read_point(&mut inner)?;
+        let top_right = read_point(&mut inner)?;
+
+        ret.push(
+            Rectangle::try_new(bottom_left, top_right).map_err(invalid_data)?
+        );
+    }
+
+    Ok(Some(ret))
+}
+
+pub fn write_document<W>(inner: W, document: &Document, frames: bool)
+    -> io::Result<W>
+    where W: Write
+{
+    let count = document.polygons.len() as u64;
+    let mut writer = Writer::new(inner, &document.header, count, frames)?;
+    let empty = Attributes::default();
+
+    for (i, polygon) in document.polygons.iter().enumerate() {
+        writer.write(polygon, document.attributes.get(i).unwrap_or(&empty))?;
+    }
+
+    writer.finish()
+}
+
+fn invalid_data<E>(error: E) -> io::Error where E: ToString
+{
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn invalid_input<E>(error: E) -> io::Error where E: ToString
+{
+    io::Error::new(io::ErrorKind::InvalidInput, error.to_string())
+}
+
+// The polygon count and whether there is a frame table.
+
+fn read_prefix<R>(inner: &mut R) -> io::Result<(u64, bool)> where R: Read
+{
+    let mut magic = [0; 8];
+
+    inner.read_exact(&mut magic)?;
+
+    if &magic != MAGIC {
+        return Err(invalid_data("not a binary polygon file"));
+    }
+
+    let version = read_u32(inner)?;
+
+    if version != VERSION {
+        return Err(invalid_data(format!("unsupported version {}", version)));
+    }
+
+    let flags = read_u32(inner)?;
+    let count = read_u64(inner)?;
+
+    Ok((count, (flags & FLAG_FRAMES) != 0))
+}
+
+fn read_point<R>(inner: &mut R) -> io::Result<Point> where R: Read
+{
+    let mut bytes = [0; 16];
+
+    inner.read_exact(&mut bytes)?;
+
+    let (x, y) = bytes.split_at(8);
+
+    Ok(
+        Point {
+            x: Unit::from_le_bytes(x.try_into().unwrap()),
+            y: Unit::from_le_bytes(y.try_into().unwrap())
+        }
+    )
+}
+
+fn read_string<R>(inner: &mut R) -> io::Result<String> where R: Read
+{
+    let len = read_u32(inner)? as u64;
+    let mut ret = String::new();
+
+    inner.take(len).read_to_string(&mut ret)?;
+
+    if ret.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(ret)
+}
+
+fn read_u32<R>(inner: &mut R) -> io::Result<u32> where R: Read
+{
+    let mut bytes = [0; 4];
+
+    inner.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R>(inner: &mut R) -> io::Result<u64> where R: Read
+{
+    let mut bytes = [0; 8];
+
+    inner.read_exact(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_point<W>(inner: &mut W, point: &Point) -> io::Result<()>
+    where W: Write
+{
+    inner.write_all(&point.x.to_le_bytes())?;
+    inner.write_all(&point.y.to_le_bytes())
+}
+
+fn write_string<W>(inner: &mut W, str: &str) -> io::Result<()>
+    where W: Write
+{
+    let len =
+        u32::try_from(str.len()).map_err(|_| invalid_input("string too long"))?;
+
+    inner.write_all(&len.to_le_bytes())?;
+    inner.write_all(str.as_bytes())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use std::io::Cursor;
+
+    fn generate_document() -> Document
+    {
+        let mut ret =
+            Document::new(
+                vec! [
+                    Rectangle::square(Point { x: -1., y: 0.5 }, 4.).polygon(),
+                    Any {
+                        points: vec! [
+                            (0.1, 0.2).into(),
+                            (1e-300, 3.).into(),
+                            (2., -7.25).into()
+                        ]
+                    }
+                ]
+            );
+
+        ret.header.units = Some("mm".to_owned());
+        ret.header.seed = Some(42);
+        ret.attributes[1].insert("name", "inner \"one\"");
+
+        ret
+    }
+
+    #[test]
+    fn test_round_trip()
+    {
+        let mut expected = generate_document();
+
+        for frames in [false, true] {
+            let bytes = write_document(Vec::new(), &expected, frames).unwrap();
+
+            assert!(is_binary(&bytes));
+
+            let testing = read_document(Cursor::new(&bytes)).unwrap();
+
+            expected.header.version = super::super::VERSION;
+
+            assert_eq!(testing, expected);
+
+            // The bits of the coordinates are kept.
+
+            assert_eq!(
+                testing.polygons[1].points[1].x.to_bits(),
+                (1e-300 as Unit).to_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn test_convert_text()
+    {
+        let document = generate_document();
+        let text = document.to_string();
+
+        for frames in [false, true] {
+            let testing =
+                convert_text(Cursor::new(&text), Vec::new(), frames).unwrap();
+
+            let expected =
+                write_document(Vec::new(), &document, frames).unwrap();
+
+            assert_eq!(testing, expected);
+        }
+
+        let error =
+            convert_text(Cursor::new("0 0 0\n@1 a=b\n"), Vec::new(), false)
+                .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_frames()
+    {
+        let document = generate_document();
+
+        let bytes = write_document(Vec::new(), &document, true).unwrap();
+
+        let expected =
+            document
+                .polygons
+                .iter()
+                .map(|polygon| polygon.frame().unwrap())
+                .collect::<Vec<_>>();
+
+        let testing = read_frames(Cursor::new(&bytes)).unwrap();
+
+        assert_eq!(testing, Some(expected));
+
+        let bytes = write_document(Vec::new(), &document, false).unwrap();
+
+        assert_eq!(read_frames(Cursor::new(&bytes)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_streaming()
+    {
+        let document = generate_document();
+        let bytes = write_document(Vec::new(), &document, false).unwrap();
+
+        let mut reader = Reader::new(Cursor::new(&bytes)).unwrap();
+
+        assert_eq!(reader.len(), 2);
+        assert_eq!(reader.header().seed, Some(42));
+
+        let (polygon, attributes) = reader.next().unwrap().unwrap();
+
+        assert_eq!(polygon, document.polygons[0]);
+        assert!(attributes.is_empty());
+
+        // A truncated file gives an error and then nothing.
+
+        let mut reader = Reader::new(&bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_errors()
+    {
+        let header = Header::default();
+        let polygon = Rectangle::square(Point::default(), 1.).polygon();
+        let empty = Attributes::default();
+
+        let mut writer = Writer::new(Vec::new(), &header, 1, true).unwrap();
+
+        assert!(writer.write(&Any::default(), &empty).is_err());
+        writer.write(&polygon, &empty).unwrap();
+        assert!(writer.write(&polygon, &empty).is_err());
+        assert!(writer.finish().is_ok());
+
+        let writer = Writer::new(Vec::new(), &header, 1, false).unwrap();
+
+        assert!(writer.finish().is_err());
+
+        let error = Reader::new(&b"0 0 0\n0 1 0\n"[..]).err().unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
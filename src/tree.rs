@@ -1,12 +1,31 @@
+use derive_more::Display;
+
 use std::cell::{Ref, RefCell};
-use std::cmp::min;
+use std::cmp::{min, Ordering};
+use std::error::Error;
 use std::collections::VecDeque;
 use std::rc::{Rc, Weak};
 
+/***********/
+/* MODULES */
+/***********/
+
+pub mod arena;
+pub use arena::Arena;
+
+pub mod diff;
+pub use diff::{diff, Diff, LengthError};
+
 /**************/
 /* STRUCTURES */
 /**************/
 
+#[derive(Copy, Clone)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+#[display(fmt = "invalid attachment: a node cannot be attached below itself")]
+pub struct CycleError;
+
 #[derive(Clone, Debug)]
 pub struct Node<T>
 {
@@ -16,7 +35,10 @@ pub struct Node<T>
     value: RefCell<T>
 }
 
+pub struct AncestorIterator<T> { next: Option<Rc<Node<T>>> }
 pub struct BFSIterator<T> { unexplored: VecDeque<Rc<Node<T>>> }
+pub struct PostorderIterator<T> { unexplored: Vec<(Rc<Node<T>>, usize)> }
+pub struct PreorderIterator<T> { unexplored: Vec<Rc<Node<T>>> }
 
 /*******************/
 /* IMPLEMENTATIONS */
@@ -48,35 +70,118 @@ impl<T> Node<T> {
         let index = *child.index.borrow();
 
         *child.parent.borrow_mut() = None;
-        self.children.borrow_mut().swap_remove(index);
+        self.children.borrow_mut().remove(index);
+        self.reindex_children(index);
+    }
+
+    pub fn adopt(self: &Rc<Self>, child: &Rc<Self>) -> Result<(), CycleError>
+    {
+        child.attach(self)
+    }
+
+    pub fn attach(self: &Rc<Self>, parent: &Rc<Self>) -> Result<(), CycleError>
+    {
+        let index = parent.children.borrow().len();
+
+        self.insert_at(parent, index)
+    }
+
+    pub fn detach(self: &Rc<Self>)
+    {
+        if let Some(parent) = self.parent() {
+            parent.abandon(self);
+        }
+    }
+
+    pub fn insert_child_at(self: &Rc<Self>, index: usize, child: &Rc<Self>)
+        -> Result<(), CycleError>
+    {
+        child.insert_at(self, index)
+    }
 
-        let count = self.children.borrow().len();
+    pub fn replace(self: &Rc<Self>, other: &Rc<Self>)
+        -> Result<(), CycleError>
+    {
+        if let Some(parent) = self.parent() {
+            if Rc::ptr_eq(self, other) {
+                return Ok(());
+            }
 
-        if count != 0 {
-            let index = min(index, count - 1);
+            let index = *self.index.borrow();
 
-            *self
-                .children
-                .borrow_mut()[index]
-                .index
-                .borrow_mut() = index;
+            other.insert_at(&parent, index)?;
+            self.detach();
         }
+
+        Ok(())
     }
 
-    pub fn adopt(self: &Rc<Self>, child: &Rc<Self>) { child.attach(self); }
+    pub fn sort_children_by<F>(&self, mut compare: F)
+        where F: FnMut(&T, &T) -> Ordering
+    {
+        self
+            .children
+            .borrow_mut()
+            .sort_by(|a, b| compare(&a.value(), &b.value()));
 
-    pub fn attach(self: &Rc<Self>, parent: &Rc<Self>)
+        self.reindex_children(0);
+    }
+
+    pub fn splice(self: &Rc<Self>)
+    {
+        let children = self.children.borrow().clone();
+
+        match self.parent() {
+            Some(parent) => {
+                let index = *self.index.borrow();
+
+                for (i, child) in children.iter().enumerate() {
+                    child.link(&parent, index + i);
+                }
+
+                self.detach();
+            }
+
+            None => {
+                for child in children {
+                    child.detach();
+                }
+            }
+        }
+    }
+
+    fn insert_at(self: &Rc<Self>, parent: &Rc<Self>, index: usize)
+        -> Result<(), CycleError>
+    {
+        let is_cycle =
+            Rc::ptr_eq(self, parent) ||
+            parent.ancestors().any(|ancestor| Rc::ptr_eq(self, &ancestor));
+
+        if is_cycle {
+            Err(CycleError)
+        } else {
+            self.link(parent, index);
+
+            Ok(())
+        }
+    }
+
+    fn link(self: &Rc<Self>, parent: &Rc<Self>, index: usize)
     {
         self.detach();
 
-        *self.index.borrow_mut() = parent.children.borrow().len();
-        *self.parent.borrow_mut() = Some(Rc::downgrade(&parent));
-        parent.children.borrow_mut().push(self.clone());
+        let index = min(index, parent.children.borrow().len());
+
+        *self.parent.borrow_mut() = Some(Rc::downgrade(parent));
+        parent.children.borrow_mut().insert(index, self.clone());
+        parent.reindex_children(index);
     }
 
-    pub fn detach(self: &Rc<Self>)
+    fn reindex_children(&self, from: usize)
     {
-        self.parent().map(|parent| parent.abandon(self));
+        for (i, child) in self.children.borrow().iter().enumerate().skip(from) {
+            *child.index.borrow_mut() = i;
+        }
     }
 
     /***********************/
@@ -119,7 +224,10 @@ impl<T> Node<T> {
         BFSIterator::new(self)
     }
 
-    pub fn children(&self) -> Ref<Vec<Rc<Node<T>>>> { self.children.borrow() }
+    pub fn children(&self) -> Ref<'_, Vec<Rc<Node<T>>>>
+    {
+        self.children.borrow()
+    }
 
     pub fn is_leaf(&self) -> bool { self.children.borrow().is_empty() }
     pub fn is_root(&self) -> bool { self.parent.borrow().is_none() }
@@ -136,13 +244,123 @@ impl<T> Node<T> {
 
     pub fn upgrade(self: &Rc<Self>)
     {
-        self
-            .grandparent()
-            .map(|grandparent| self.attach(&grandparent.clone()));
+        if let Some(grandparent) = self.grandparent() {
+            let index = grandparent.children.borrow().len();
+
+            self.link(&grandparent, index);
+        }
     }
 
-    pub fn value(&self) -> Ref<T> { self.value.borrow() }
+    pub fn value(&self) -> Ref<'_, T> { self.value.borrow() }
     pub fn set_value(&self, value: T) { *self.value.borrow_mut() = value; }
+
+    /***********/
+    /* QUERIES */
+    /***********/
+
+    pub fn depth(&self) -> usize { self.ancestors().count() }
+
+    pub fn find<F>(self: &Rc<Self>, predicate: F) -> Option<Rc<Self>>
+        where F: Fn(&T) -> bool
+    {
+        self.preorder_iter().find(|node| predicate(&node.value()))
+    }
+
+    pub fn height(self: &Rc<Self>) -> usize
+    {
+        let mut ret = 0;
+        let mut unexplored = vec! [(self.clone(), 0)];
+
+        while let Some((node, level)) = unexplored.pop() {
+            ret = ret.max(level);
+
+            for child in node.children().iter() {
+                unexplored.push((child.clone(), level + 1));
+            }
+        }
+
+        ret
+    }
+
+    pub fn lowest_common_ancestor(self: &Rc<Self>, other: &Rc<Self>)
+        -> Option<Rc<Self>>
+    {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        let (mut depth_a, mut depth_b) = (a.depth(), b.depth());
+
+        while depth_a > depth_b {
+            a = a.parent()?;
+            depth_a -= 1;
+        }
+
+        while depth_b > depth_a {
+            b = b.parent()?;
+            depth_b -= 1;
+        }
+
+        while !Rc::ptr_eq(&a, &b) {
+            a = a.parent()?;
+            b = b.parent()?;
+        }
+
+        Some(a)
+    }
+
+    pub fn size(self: &Rc<Self>) -> usize { self.preorder_iter().count() }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn ancestors(&self) -> AncestorIterator<T>
+    {
+        AncestorIterator { next: self.parent() }
+    }
+
+    pub fn descendants(self: &Rc<Self>) -> impl Iterator<Item = Rc<Self>>
+    {
+        self.preorder_iter().skip(1)
+    }
+
+    pub fn leaves(self: &Rc<Self>) -> impl Iterator<Item = Rc<Self>>
+    {
+        self.preorder_iter().filter(|node| node.is_leaf())
+    }
+
+    pub fn postorder(self: &Rc<Self>) -> Vec<Rc<Self>>
+    {
+        self.postorder_iter().collect()
+    }
+
+    pub fn postorder_iter(self: &Rc<Self>) -> PostorderIterator<T>
+    {
+        PostorderIterator { unexplored: vec! [(self.clone(), 0)] }
+    }
+
+    pub fn preorder(self: &Rc<Self>) -> Vec<Rc<Self>>
+    {
+        self.preorder_iter().collect()
+    }
+
+    pub fn preorder_iter(self: &Rc<Self>) -> PreorderIterator<T>
+    {
+        PreorderIterator { unexplored: vec! [self.clone()] }
+    }
+}
+
+impl Error for CycleError {}
+
+impl<T> Iterator for AncestorIterator<T> {
+    type Item = Rc<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.next.take()?;
+
+        self.next = ret.parent();
+
+        Some(ret)
+    }
 }
 
 impl<T> BFSIterator<T> {
@@ -174,6 +392,40 @@ impl<T> Iterator for BFSIterator<T> {
     }
 }
 
+impl<T> Iterator for PostorderIterator<T> {
+    type Item = Rc<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        loop {
+            let (node, index) = self.unexplored.last_mut()?;
+            let child = node.children().get(*index).cloned();
+
+            match child {
+                Some(child) => {
+                    *index += 1;
+                    self.unexplored.push((child, 0));
+                }
+
+                None => return self.unexplored.pop().map(|(node, _)| node)
+            }
+        }
+    }
+}
+
+impl<T> Iterator for PreorderIterator<T> {
+    type Item = Rc<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.unexplored.pop()?;
+
+        self.unexplored.extend(ret.children().iter().rev().cloned());
+
+        Some(ret)
+    }
+}
+
 #[cfg(test)]
 mod tests
 {
@@ -188,11 +440,11 @@ mod tests
         let e = Node::new(4);
         let f = Node::new(5);
 
-        a.adopt(&b);
-        a.adopt(&c);
-        a.adopt(&d);
-        b.adopt(&e);
-        b.adopt(&f);
+        a.adopt(&b).unwrap();
+        a.adopt(&c).unwrap();
+        a.adopt(&d).unwrap();
+        b.adopt(&e).unwrap();
+        b.adopt(&f).unwrap();
 
         (a, f)
     }
@@ -229,6 +481,99 @@ mod tests
         assert!(Rc::ptr_eq(&a, &ancestor.unwrap()));
     }
 
+    /***********/
+    /* QUERIES */
+    /***********/
+
+    fn values(nodes: Vec<Rc<Node<i32>>>) -> Vec<i32>
+    {
+        nodes.iter().map(|x| *x.value()).collect()
+    }
+
+    #[test]
+    fn test_ancestors()
+    {
+        let (_a, f) = generate_tree();
+
+        assert_eq!(values(f.ancestors().collect()), vec! [1, 0]);
+    }
+
+    #[test]
+    fn test_depth_and_height()
+    {
+        let (a, f) = generate_tree();
+
+        assert_eq!(a.depth(), 0);
+        assert_eq!(f.depth(), 2);
+        assert_eq!(a.height(), 2);
+        assert_eq!(f.height(), 0);
+    }
+
+    #[test]
+    fn test_descendants()
+    {
+        let (a, _) = generate_tree();
+
+        assert_eq!(values(a.descendants().collect()), vec! [1, 4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_find()
+    {
+        let (a, f) = generate_tree();
+
+        assert!(Rc::ptr_eq(&a.find(|&x| x == 5).unwrap(), &f));
+        assert!(a.find(|&x| x == 6).is_none());
+    }
+
+    #[test]
+    fn test_leaves()
+    {
+        let (a, _) = generate_tree();
+
+        assert_eq!(values(a.leaves().collect()), vec! [4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_lowest_common_ancestor()
+    {
+        let (a, f) = generate_tree();
+
+        let b = a.find(|&x| x == 1).unwrap();
+        let c = a.find(|&x| x == 2).unwrap();
+        let e = a.find(|&x| x == 4).unwrap();
+
+        assert!(Rc::ptr_eq(&e.lowest_common_ancestor(&f).unwrap(), &b));
+        assert!(Rc::ptr_eq(&f.lowest_common_ancestor(&c).unwrap(), &a));
+        assert!(Rc::ptr_eq(&f.lowest_common_ancestor(&b).unwrap(), &b));
+        assert!(f.lowest_common_ancestor(&Node::new(6)).is_none());
+    }
+
+    #[test]
+    fn test_postorder()
+    {
+        let (a, _) = generate_tree();
+
+        assert_eq!(values(a.postorder()), vec! [4, 5, 1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_preorder()
+    {
+        let (a, _) = generate_tree();
+
+        assert_eq!(values(a.preorder()), vec! [0, 1, 4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_size()
+    {
+        let (a, f) = generate_tree();
+
+        assert_eq!(a.size(), 6);
+        assert_eq!(f.parent().unwrap().size(), 3);
+    }
+
     /***********/
     /* ACTIONS */
     /***********/
@@ -239,7 +584,7 @@ mod tests
         let a = Node::new(0);
         let b = Node::new(1);
 
-        a.adopt(&b);
+        a.adopt(&b).unwrap();
 
         let testing = a.children().first().unwrap().clone();
 
@@ -249,6 +594,32 @@ mod tests
         assert!(Rc::ptr_eq(&testing.parent().unwrap(), &a));
     }
 
+    #[test]
+    fn test_abandon()
+    {
+        let (a, _) = generate_tree();
+
+        let b = a.find(|&x| x == 1).unwrap();
+
+        a.abandon(&b);
+
+        assert_eq!(values(a.children().clone()), vec! [2, 3]);
+        assert_eq!(*a.children()[1].index.borrow(), 1);
+        assert!(b.is_root());
+    }
+
+    #[test]
+    fn test_attach_cycle()
+    {
+        let (a, f) = generate_tree();
+
+        let b = f.parent().unwrap();
+
+        assert_eq!(a.attach(&f), Err(CycleError));
+        assert_eq!(b.attach(&b), Err(CycleError));
+        assert!(Rc::ptr_eq(&f.parent().unwrap(), &b));
+    }
+
     #[test]
     fn test_bfs()
     {
@@ -271,6 +642,62 @@ mod tests
         assert!(f.is_root());
     }
 
+    #[test]
+    fn test_insert_child_at()
+    {
+        let (a, f) = generate_tree();
+
+        a.insert_child_at(1, &f).unwrap();
+
+        assert_eq!(values(a.children().clone()), vec! [1, 5, 2, 3]);
+        assert_eq!(values(a.preorder()), vec! [0, 1, 4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_replace()
+    {
+        let (a, _) = generate_tree();
+
+        let b = a.find(|&x| x == 1).unwrap();
+        let g = Node::new(6);
+
+        b.replace(&g).unwrap();
+
+        assert_eq!(values(a.children().clone()), vec! [6, 2, 3]);
+        assert!(b.is_root());
+        assert_eq!(b.size(), 3);
+        assert_eq!(b.replace(&a), Ok(()));
+    }
+
+    #[test]
+    fn test_sort_children_by()
+    {
+        let (a, _) = generate_tree();
+
+        a.sort_children_by(|x, y| y.cmp(x));
+
+        assert_eq!(values(a.preorder()), vec! [0, 3, 2, 1, 4, 5]);
+        assert_eq!(*a.children()[2].index.borrow(), 2);
+    }
+
+    #[test]
+    fn test_splice()
+    {
+        let (a, _) = generate_tree();
+
+        let b = a.find(|&x| x == 1).unwrap();
+
+        b.splice();
+
+        assert_eq!(values(a.children().clone()), vec! [4, 5, 2, 3]);
+        assert!(b.is_root());
+        assert!(b.is_leaf());
+
+        for (i, child) in a.children().iter().enumerate() {
+            assert_eq!(*child.index.borrow(), i);
+        }
+    }
+
     #[test]
     fn test_upgrade()
     {
@@ -0,0 +1,949 @@
+use super::{CycleError, Node};
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Debug, Default)]
+pub struct Arena<T> { nodes: Vec<Entry<T>> }
+
+#[derive(Clone, Debug)]
+struct Entry<T>
+{
+    first_child: Option<usize>,
+    last_child: Option<usize>,
+    next_sibling: Option<usize>,
+    parent: Option<usize>,
+    previous_sibling: Option<usize>,
+    value: T
+}
+
+pub struct AncestorIterator<'a, T>
+{
+    arena: &'a Arena<T>,
+    next: Option<usize>
+}
+
+pub struct BFSIterator<'a, T>
+{
+    arena: &'a Arena<T>,
+    unexplored: VecDeque<usize>
+}
+
+pub struct ChildIterator<'a, T>
+{
+    arena: &'a Arena<T>,
+    back: Option<usize>,
+    front: Option<usize>
+}
+
+pub struct PostorderIterator<'a, T>
+{
+    arena: &'a Arena<T>,
+    unexplored: Vec<(usize, Option<usize>)>
+}
+
+pub struct PreorderIterator<'a, T>
+{
+    arena: &'a Arena<T>,
+    unexplored: Vec<usize>
+}
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl<T> Arena<T> {
+    /****************/
+    /* CONSTRUCTORS */
+    /****************/
+
+    pub fn new() -> Self { Self { nodes: Vec::new() } }
+
+    pub fn with_capacity(capacity: usize) -> Self
+    {
+        Self { nodes: Vec::with_capacity(capacity) }
+    }
+
+    pub fn from_node(root: &Rc<Node<T>>) -> Self where T: Clone
+    {
+        let mut ret = Self::new();
+        let mut unexplored = VecDeque::new();
+
+        unexplored.push_back((root.clone(), None));
+
+        while let Some((node, parent)) = unexplored.pop_front() {
+            let id = ret.insert(node.value().clone());
+
+            if let Some(parent) = parent {
+                ret.link(id, parent, None);
+            }
+
+            for child in node.children().iter() {
+                unexplored.push_back((child.clone(), Some(id)));
+            }
+        }
+
+        ret
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    pub fn adopt(&mut self, parent: usize, child: usize)
+        -> Result<(), CycleError>
+    {
+        self.attach(child, parent)
+    }
+
+    pub fn attach(&mut self, child: usize, parent: usize)
+        -> Result<(), CycleError>
+    {
+        self.check_cycle(child, parent)?;
+        self.link(child, parent, None);
+
+        Ok(())
+    }
+
+    pub fn detach(&mut self, child: usize)
+    {
+        if let Some(parent) = self.parent(child) {
+            self.abandon(parent, child);
+        }
+    }
+
+    pub fn insert_child_at(&mut self, parent: usize, index: usize, child: usize)
+        -> Result<(), CycleError>
+    {
+        self.check_cycle(child, parent)?;
+        self.detach(child);
+
+        let before = self.children(parent).nth(index);
+
+        self.link(child, parent, before);
+
+        Ok(())
+    }
+
+    // Like `Node::replace`, leaves the arena as is when `old` is a root, which
+    // has no place for `new` to take.
+
+    pub fn replace(&mut self, old: usize, new: usize) -> Result<(), CycleError>
+    {
+        if let Some(parent) = self.parent(old) {
+            if old == new {
+                return Ok(());
+            }
+
+            self.check_cycle(new, parent)?;
+            self.detach(new);
+            self.link(new, parent, Some(old));
+            self.detach(old);
+        }
+
+        Ok(())
+    }
+
+    pub fn sort_children_by<F>(&mut self, id: usize, mut compare: F)
+        where F: FnMut(&T, &T) -> Ordering
+    {
+        let mut children = self.children(id).collect::<Vec<_>>();
+
+        children.sort_by(|&a, &b| compare(self.value(a), self.value(b)));
+
+        for child in children {
+            self.link(child, id, None);
+        }
+    }
+
+    pub fn splice(&mut self, id: usize)
+    {
+        let parent = self.parent(id);
+
+        while let Some(child) = self.nodes[id].first_child {
+            match parent {
+                Some(parent) => self.link(child, parent, Some(id)),
+                None => self.detach(child)
+            }
+        }
+
+        self.detach(id);
+    }
+
+    // Removes a node, whose children become roots, and moves the last node of
+    // the arena into its slot so that no slot is left unused. Any index of the
+    // last node held outside the arena is then stale, hence only `Hierarchy`,
+    // which remaps its own, may call it.
+
+    pub(crate) fn swap_remove(&mut self, id: usize) -> T
+    {
+        while let Some(child) = self.nodes[id].first_child {
+            self.detach(child);
+        }
+
+        self.detach(id);
+
+        let last = self.nodes.len() - 1;
+
+        if id != last {
+            let entry = &self.nodes[last];
+            let parent = entry.parent;
+            let (previous, next) = (entry.previous_sibling, entry.next_sibling);
+
+            if let Some(previous) = previous {
+                self.nodes[previous].next_sibling = Some(id);
+            } else if let Some(parent) = parent {
+                self.nodes[parent].first_child = Some(id);
+            }
+
+            if let Some(next) = next {
+                self.nodes[next].previous_sibling = Some(id);
+            } else if let Some(parent) = parent {
+                self.nodes[parent].last_child = Some(id);
+            }
+
+            let children = self.children(last).collect::<Vec<_>>();
+
+            for child in children {
+                self.nodes[child].parent = Some(id);
+            }
+        }
+
+        self.nodes.swap_remove(id).value
+    }
+
+    // `parent` must be the parent of `child`, which `detach` makes sure of.
+
+    fn abandon(&mut self, parent: usize, child: usize)
+    {
+        let previous = self.nodes[child].previous_sibling.take();
+        let next = self.nodes[child].next_sibling.take();
+
+        match previous {
+            Some(previous) => self.nodes[previous].next_sibling = next,
+            None => self.nodes[parent].first_child = next
+        }
+
+        match next {
+            Some(next) => self.nodes[next].previous_sibling = previous,
+            None => self.nodes[parent].last_child = previous
+        }
+
+        self.nodes[child].parent = None;
+    }
+
+    fn check_cycle(&self, child: usize, parent: usize) -> Result<(), CycleError>
+    {
+        if (child == parent) || self.ancestors(parent).any(|a| a == child) {
+            Err(CycleError)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn link(&mut self, child: usize, parent: usize, before: Option<usize>)
+    {
+        self.detach(child);
+
+        let previous =
+            match before {
+                Some(before) => self.nodes[before].previous_sibling,
+                None => self.nodes[parent].last_child
+            };
+
+        match previous {
+            Some(previous) => self.nodes[previous].next_sibling = Some(child),
+            None => self.nodes[parent].first_child = Some(child)
+        }
+
+        match before {
+            Some(before) => self.nodes[before].previous_sibling = Some(child),
+            None => self.nodes[parent].last_child = Some(child)
+        }
+
+        let entry = &mut self.nodes[child];
+
+        entry.next_sibling = before;
+        entry.parent = Some(parent);
+        entry.previous_sibling = previous;
+    }
+
+    pub fn insert(&mut self, value: T) -> usize
+    {
+        self.nodes.push(
+            Entry {
+                first_child: None,
+                last_child: None,
+                next_sibling: None,
+                parent: None,
+                previous_sibling: None,
+                value
+            }
+        );
+
+        self.nodes.len() - 1
+    }
+
+    pub fn upgrade(&mut self, child: usize)
+    {
+        if let Some(grandparent) = self.grandparent(child) {
+            self.link(child, grandparent, None);
+        }
+    }
+
+    /***********************/
+    /* GETTERS AND SETTERS */
+    /***********************/
+
+    pub fn above(&self, id: usize, n: usize)
+        -> Result<usize, (usize, Option<usize>)>
+    {
+        match self.parent(id) {
+            Some(mut parent) => {
+                let mut i = n - 1;
+
+                while let Some(grandparent) = self.parent(parent) {
+                    if i == 0 {
+                        break;
+                    } else {
+                        parent = grandparent;
+                        i -= 1;
+                    }
+                }
+
+                if i == 0 {
+                    Ok(parent)
+                } else {
+                    Err((n - i, Some(parent)))
+                }
+            }
+
+            None => Err((0, None))
+        }
+    }
+
+    pub fn bfs(&self, id: usize) -> Vec<usize> { self.bfs_iter(id).collect() }
+
+    pub fn bfs_iter(&self, id: usize) -> BFSIterator<'_, T>
+    {
+        BFSIterator::new(self, id)
+    }
+
+    pub fn children(&self, id: usize) -> ChildIterator<'_, T>
+    {
+        ChildIterator {
+            arena: self,
+            back: self.nodes[id].last_child,
+            front: self.nodes[id].first_child
+        }
+    }
+
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+    pub fn is_leaf(&self, id: usize) -> bool
+    {
+        self.nodes[id].first_child.is_none()
+    }
+    pub fn is_root(&self, id: usize) -> bool { self.parent(id).is_none() }
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    pub fn grandparent(&self, id: usize) -> Option<usize>
+    {
+        self.parent(id).and_then(|parent| self.parent(parent))
+    }
+
+    pub fn parent(&self, id: usize) -> Option<usize> { self.nodes[id].parent }
+
+    pub fn value(&self, id: usize) -> &T { &self.nodes[id].value }
+
+    pub fn value_mut(&mut self, id: usize) -> &mut T
+    {
+        &mut self.nodes[id].value
+    }
+
+    pub fn set_value(&mut self, id: usize, value: T)
+    {
+        self.nodes[id].value = value;
+    }
+
+    /***********/
+    /* QUERIES */
+    /***********/
+
+    pub fn depth(&self, id: usize) -> usize { self.ancestors(id).count() }
+
+    pub fn find<F>(&self, id: usize, predicate: F) -> Option<usize>
+        where F: Fn(&T) -> bool
+    {
+        self.preorder_iter(id).find(|&node| predicate(self.value(node)))
+    }
+
+    pub fn height(&self, id: usize) -> usize
+    {
+        let mut ret = 0;
+        let mut unexplored = vec! [(id, 0)];
+
+        while let Some((node, level)) = unexplored.pop() {
+            ret = ret.max(level);
+
+            for child in self.children(node) {
+                unexplored.push((child, level + 1));
+            }
+        }
+
+        ret
+    }
+
+    pub fn lowest_common_ancestor(&self, a: usize, b: usize) -> Option<usize>
+    {
+        let (mut a, mut b) = (a, b);
+        let (mut depth_a, mut depth_b) = (self.depth(a), self.depth(b));
+
+        while depth_a > depth_b {
+            a = self.parent(a)?;
+            depth_a -= 1;
+        }
+
+        while depth_b > depth_a {
+            b = self.parent(b)?;
+            depth_b -= 1;
+        }
+
+        while a != b {
+            a = self.parent(a)?;
+            b = self.parent(b)?;
+        }
+
+        Some(a)
+    }
+
+    pub fn size(&self, id: usize) -> usize { self.preorder_iter(id).count() }
+
+    /*************/
+    /* ITERATORS */
+    /*************/
+
+    pub fn ancestors(&self, id: usize) -> AncestorIterator<'_, T>
+    {
+        AncestorIterator { arena: self, next: self.parent(id) }
+    }
+
+    pub fn descendants(&self, id: usize) -> impl Iterator<Item = usize> + '_
+    {
+        self.preorder_iter(id).skip(1)
+    }
+
+    pub fn leaves(&self, id: usize) -> impl Iterator<Item = usize> + '_
+    {
+        self.preorder_iter(id).filter(|&node| self.is_leaf(node))
+    }
+
+    pub fn postorder(&self, id: usize) -> Vec<usize>
+    {
+        self.postorder_iter(id).collect()
+    }
+
+    pub fn postorder_iter(&self, id: usize) -> PostorderIterator<'_, T>
+    {
+        let unexplored = vec! [(id, self.nodes[id].first_child)];
+
+        PostorderIterator { arena: self, unexplored }
+    }
+
+    pub fn preorder(&self, id: usize) -> Vec<usize>
+    {
+        self.preorder_iter(id).collect()
+    }
+
+    pub fn preorder_iter(&self, id: usize) -> PreorderIterator<'_, T>
+    {
+        PreorderIterator { arena: self, unexplored: vec! [id] }
+    }
+
+    /***************/
+    /* CONVERSIONS */
+    /***************/
+
+    // The same tree, with `f` applied to every value.
+
+    pub fn map<U, F>(self, mut f: F) -> Arena<U> where F: FnMut(T) -> U
+    {
+        let nodes =
+            self
+                .nodes
+                .into_iter()
+                .map(
+                    |entry| Entry {
+                        first_child: entry.first_child,
+                        last_child: entry.last_child,
+                        next_sibling: entry.next_sibling,
+                        parent: entry.parent,
+                        previous_sibling: entry.previous_sibling,
+                        value: f(entry.value)
+                    }
+                )
+                .collect();
+
+        Arena { nodes }
+    }
+
+    pub fn to_node(&self, id: usize) -> Rc<Node<T>> where T: Clone
+    {
+        let ret = Node::new(self.value(id).clone());
+
+        for child in self.children(id) {
+            ret.adopt(&self.to_node(child)).unwrap();
+        }
+
+        ret
+    }
+}
+
+impl<T> Iterator for AncestorIterator<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.next.take()?;
+
+        self.next = self.arena.parent(ret);
+
+        Some(ret)
+    }
+}
+
+impl<'a, T> BFSIterator<'a, T> {
+    pub fn new(arena: &'a Arena<T>, id: usize) -> Self
+    {
+        let mut unexplored = VecDeque::new();
+        unexplored.push_back(id);
+
+        Self { arena, unexplored }
+    }
+}
+
+impl<T> Iterator for BFSIterator<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.unexplored.pop_front()?;
+
+        self.unexplored.extend(self.arena.children(ret));
+
+        Some(ret)
+    }
+}
+
+impl<T> Iterator for ChildIterator<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.front?;
+
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.arena.nodes[ret].next_sibling;
+        }
+
+        Some(ret)
+    }
+}
+
+impl<T> DoubleEndedIterator for ChildIterator<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.back?;
+
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.arena.nodes[ret].previous_sibling;
+        }
+
+        Some(ret)
+    }
+}
+
+impl<T> Iterator for PostorderIterator<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        loop {
+            let (_, next) = self.unexplored.last_mut()?;
+
+            match *next {
+                Some(child) => {
+                    *next = self.arena.nodes[child].next_sibling;
+
+                    let first = self.arena.nodes[child].first_child;
+
+                    self.unexplored.push((child, first));
+                }
+
+                None => return self.unexplored.pop().map(|(node, _)| node)
+            }
+        }
+    }
+}
+
+impl<T> Iterator for PreorderIterator<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let ret = self.unexplored.pop()?;
+
+        self.unexplored.extend(self.arena.children(ret).rev());
+
+        Some(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn generate_tree() -> Arena<i32>
+    {
+        let mut ret = Arena::new();
+
+        for i in 0..6 {
+            ret.insert(i);
+        }
+
+        ret.adopt(0, 1).unwrap();
+        ret.adopt(0, 2).unwrap();
+        ret.adopt(0, 3).unwrap();
+        ret.adopt(1, 4).unwrap();
+        ret.adopt(1, 5).unwrap();
+
+        ret
+    }
+
+    fn is_send_and_sync<T: Send + Sync>() {}
+
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    #[test]
+    fn test_above_correct()
+    {
+        assert_eq!(generate_tree().above(5, 2), Ok(0));
+    }
+
+    #[test]
+    fn test_above_root()
+    {
+        assert_eq!(generate_tree().above(0, 0), Err((0, None)));
+    }
+
+    #[test]
+    fn test_above_too_much()
+    {
+        assert_eq!(generate_tree().above(5, 5), Err((2, Some(0))));
+    }
+
+    #[test]
+    fn test_is_send_and_sync() { is_send_and_sync::<Arena<i32>>(); }
+
+    /***********/
+    /* QUERIES */
+    /***********/
+
+    #[test]
+    fn test_ancestors()
+    {
+        let testing = generate_tree();
+
+        assert_eq!(testing.ancestors(5).collect::<Vec<_>>(), vec! [1, 0]);
+    }
+
+    #[test]
+    fn test_depth_and_height()
+    {
+        let testing = generate_tree();
+
+        assert_eq!(testing.depth(0), 0);
+        assert_eq!(testing.depth(5), 2);
+        assert_eq!(testing.height(0), 2);
+        assert_eq!(testing.height(5), 0);
+    }
+
+    #[test]
+    fn test_descendants()
+    {
+        let testing = generate_tree();
+
+        let expected = vec! [1, 4, 5, 2, 3];
+
+        assert_eq!(testing.descendants(0).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_find()
+    {
+        let testing = generate_tree();
+
+        assert_eq!(testing.find(0, |&x| x == 5), Some(5));
+        assert_eq!(testing.find(2, |&x| x == 5), None);
+    }
+
+    #[test]
+    fn test_leaves()
+    {
+        let testing = generate_tree();
+
+        assert_eq!(testing.leaves(0).collect::<Vec<_>>(), vec! [4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_lowest_common_ancestor()
+    {
+        let mut testing = generate_tree();
+
+        let orphan = testing.insert(6);
+
+        assert_eq!(testing.lowest_common_ancestor(4, 5), Some(1));
+        assert_eq!(testing.lowest_common_ancestor(5, 2), Some(0));
+        assert_eq!(testing.lowest_common_ancestor(5, 1), Some(1));
+        assert_eq!(testing.lowest_common_ancestor(5, orphan), None);
+    }
+
+    #[test]
+    fn test_postorder()
+    {
+        assert_eq!(generate_tree().postorder(0), vec! [4, 5, 1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_preorder()
+    {
+        assert_eq!(generate_tree().preorder(0), vec! [0, 1, 4, 5, 2, 3]);
+    }
+
+    #[test]
+    fn test_size()
+    {
+        let testing = generate_tree();
+
+        assert_eq!(testing.size(0), 6);
+        assert_eq!(testing.size(1), 3);
+    }
+
+    /***********/
+    /* ACTIONS */
+    /***********/
+
+    #[test]
+    fn test_adopt()
+    {
+        let mut testing = Arena::new();
+
+        let a = testing.insert(0);
+        let b = testing.insert(1);
+
+        testing.adopt(a, b).unwrap();
+
+        assert_eq!(testing.children(a).collect::<Vec<_>>(), vec! [b]);
+        assert_eq!(*testing.value(b), 1);
+        assert_eq!(testing.parent(b), Some(a));
+    }
+
+    #[test]
+    fn test_abandon()
+    {
+        let mut testing = generate_tree();
+
+        testing.abandon(0, 2);
+
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [1, 3]);
+        assert_eq!(testing.children(0).rev().collect::<Vec<_>>(), vec! [3, 1]);
+        assert!(testing.is_root(2));
+    }
+
+    #[test]
+    fn test_attach_cycle()
+    {
+        let mut testing = generate_tree();
+
+        assert_eq!(testing.attach(0, 5), Err(CycleError));
+        assert_eq!(testing.attach(1, 1), Err(CycleError));
+        assert_eq!(testing.parent(5), Some(1));
+        assert_eq!(testing.attach(1, 3), Ok(()));
+        assert_eq!(testing.preorder(0), vec! [0, 2, 3, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_bfs()
+    {
+        let testing = generate_tree();
+
+        for (i, item) in testing.bfs(0).into_iter().enumerate() {
+            assert_eq!(*testing.value(item), i as i32);
+        }
+    }
+
+    #[test]
+    fn test_detach()
+    {
+        let mut testing = generate_tree();
+
+        testing.detach(5);
+
+        assert_eq!(testing.bfs(0), vec! [0, 1, 2, 3, 4]);
+        assert!(testing.is_root(5));
+    }
+
+    #[test]
+    fn test_insert_child_at()
+    {
+        let mut testing = generate_tree();
+
+        testing.insert_child_at(0, 1, 5).unwrap();
+
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [1, 5, 2, 3]);
+        assert_eq!(testing.insert_child_at(5, 0, 0), Err(CycleError));
+    }
+
+    #[test]
+    fn test_replace()
+    {
+        let mut testing = generate_tree();
+
+        let orphan = testing.insert(6);
+
+        testing.replace(1, orphan).unwrap();
+
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [6, 2, 3]);
+        assert!(testing.is_root(1));
+        assert_eq!(testing.size(1), 3);
+    }
+
+    #[test]
+    fn test_replace_root()
+    {
+        let mut testing = generate_tree();
+
+        let orphan = testing.insert(6);
+
+        assert_eq!(testing.replace(0, orphan), Ok(()));
+        assert_eq!(testing.replace(0, 1), Ok(()));
+        assert!(testing.is_root(0));
+        assert!(testing.is_root(orphan));
+        assert_eq!(testing.preorder(0), generate_tree().preorder(0));
+    }
+
+    #[test]
+    fn test_sort_children_by()
+    {
+        let mut testing = generate_tree();
+
+        testing.sort_children_by(0, |x, y| y.cmp(x));
+
+        assert_eq!(testing.preorder(0), vec! [0, 3, 2, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_splice()
+    {
+        let mut testing = generate_tree();
+
+        testing.splice(1);
+
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [4, 5, 2, 3]);
+        assert!(testing.is_root(1));
+        assert!(testing.is_leaf(1));
+    }
+
+    #[test]
+    fn test_splice_root()
+    {
+        let mut testing = generate_tree();
+
+        testing.splice(0);
+
+        assert!(testing.is_root(1));
+        assert!(testing.is_root(2));
+        assert_eq!(testing.preorder(1), vec! [1, 4, 5]);
+    }
+
+    #[test]
+    fn test_swap_remove()
+    {
+        let mut testing = generate_tree();
+
+        assert_eq!(testing.swap_remove(2), 2);
+        assert_eq!(testing.len(), 5);
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [1, 3]);
+        assert_eq!(testing.children(1).collect::<Vec<_>>(), vec! [4, 2]);
+        assert_eq!(*testing.value(2), 5);
+
+        assert_eq!(testing.swap_remove(1), 1);
+        assert_eq!(testing.len(), 4);
+        assert_eq!(testing.children(0).collect::<Vec<_>>(), vec! [3]);
+        assert!(testing.is_root(1));
+        assert!(testing.is_root(2));
+        assert_eq!(*testing.value(1), 4);
+
+        assert_eq!(testing.swap_remove(3), 3);
+        assert_eq!(testing.preorder(0), vec! [0]);
+    }
+
+    #[test]
+    fn test_upgrade()
+    {
+        let mut testing = generate_tree();
+
+        testing.upgrade(5);
+
+        assert_eq!(testing.bfs(0), vec! [0, 1, 2, 3, 5, 4]);
+        assert_eq!(testing.parent(5), Some(0));
+    }
+
+    /***************/
+    /* CONVERSIONS */
+    /***************/
+
+    #[test]
+    fn test_map()
+    {
+        let testing = generate_tree().map(|x| x * 10);
+
+        assert_eq!(testing.bfs(0), generate_tree().bfs(0));
+        assert_eq!(*testing.value(5), 50);
+        assert_eq!(testing.parent(5), Some(1));
+    }
+
+    #[test]
+    fn test_to_node_and_back()
+    {
+        let testing = generate_tree();
+        let node = testing.to_node(0);
+
+        let values =
+            node
+            .bfs()
+            .iter()
+            .map(|x| *x.value())
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, vec! [0, 1, 2, 3, 4, 5]);
+
+        let testing = Arena::from_node(&node);
+
+        assert_eq!(testing.bfs(0), vec! [0, 1, 2, 3, 4, 5]);
+        assert_eq!(testing.parent(5), Some(1));
+    }
+}
@@ -0,0 +1,191 @@
+use super::Arena;
+
+use derive_more::Display;
+
+use std::error::Error;
+
+/**************/
+/* STRUCTURES */
+/**************/
+
+#[derive(Clone, Copy)]
+#[derive(Debug, Eq, PartialEq)]
+pub struct Change<T> { pub id: usize, pub before: T, pub after: T }
+
+// `subtrees` holds the nodes which moved with their whole subtree of the tree
+// before, none of their descendants there having changed parents. A moved node
+// with a moved descendant is left out, whatever the tree after.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diff
+{
+    pub depths: Vec<Change<usize>>,
+    pub parents: Vec<Change<Option<usize>>>,
+    pub subtrees: Vec<usize>
+}
+
+#[derive(Copy, Clone)]
+#[derive(Debug, Eq, PartialEq)]
+#[derive(Display)]
+#[display(fmt = "trees of different lengths: {} and {}", before, after)]
+pub struct LengthError { pub before: usize, pub after: usize }
+
+/*******************/
+/* IMPLEMENTATIONS */
+/*******************/
+
+impl Diff {
+    /***********/
+    /* GETTERS */
+    /***********/
+
+    pub fn is_empty(&self) -> bool { self.parents.is_empty() }
+}
+
+impl Error for LengthError {}
+
+/*************/
+/* FUNCTIONS */
+/*************/
+
+// Compares two trees over the same nodes, so of the same length.
+
+pub fn diff<T, U>(before: &Arena<T>, after: &Arena<U>)
+    -> Result<Diff, LengthError>
+{
+    if before.len() != after.len() {
+        return Err(LengthError { before: before.len(), after: after.len() });
+    }
+
+    let parents =
+        (0..before.len())
+            .map(
+                |id| {
+                    let (a, b) = (before.parent(id), after.parent(id));
+
+                    Change { id, before: a, after: b }
+                }
+            )
+            .filter(|change| change.before != change.after)
+            .collect::<Vec<_>>();
+
+    let is_moved = {
+        let mut ret = vec! [false; before.len()];
+
+        for change in &parents {
+            ret[change.id] = true;
+        }
+
+        ret
+    };
+
+    let depths =
+        (0..before.len())
+            .map(
+                |id| {
+                    let (a, b) = (before.depth(id), after.depth(id));
+
+                    Change { id, before: a, after: b }
+                }
+            )
+            .filter(|change| change.before != change.after)
+            .collect();
+
+    let subtrees =
+        parents
+            .iter()
+            .map(|change| change.id)
+            .filter(|&id| !before.descendants(id).any(|child| is_moved[child]))
+            .collect();
+
+    Ok(Diff { depths, parents, subtrees })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn generate_tree() -> Arena<i32>
+    {
+        let mut ret = Arena::new();
+
+        for i in 0..6 {
+            ret.insert(i);
+        }
+
+        ret.adopt(0, 1).unwrap();
+        ret.adopt(0, 2).unwrap();
+        ret.adopt(0, 3).unwrap();
+        ret.adopt(1, 4).unwrap();
+        ret.adopt(4, 5).unwrap();
+
+        ret
+    }
+
+    #[test]
+    fn test_diff_identical()
+    {
+        let testing = diff(&generate_tree(), &generate_tree()).unwrap();
+
+        assert!(testing.is_empty());
+        assert_eq!(testing, Diff::default());
+    }
+
+    #[test]
+    fn test_diff_moved_subtree()
+    {
+        let before = generate_tree();
+        let mut after = generate_tree();
+
+        after.attach(4, 2).unwrap();
+        after.attach(1, 3).unwrap();
+
+        let testing = diff(&before, &after).unwrap();
+
+        let expected_parents = vec! [
+            Change { id: 1, before: Some(0), after: Some(3) },
+            Change { id: 4, before: Some(1), after: Some(2) }
+        ];
+
+        let expected_depths = vec! [
+            Change { id: 1, before: 1, after: 2 }
+        ];
+
+        assert_eq!(testing.parents, expected_parents);
+        assert_eq!(testing.depths, expected_depths);
+        assert_eq!(testing.subtrees, vec! [4]);
+    }
+
+    #[test]
+    fn test_diff_lengths()
+    {
+        let before = generate_tree();
+        let mut after = generate_tree();
+
+        after.insert(6);
+
+        let expected = LengthError { before: 6, after: 7 };
+
+        assert_eq!(diff(&before, &after), Err(expected));
+    }
+
+    #[test]
+    fn test_diff_depths()
+    {
+        let before = generate_tree();
+        let mut after = generate_tree();
+
+        after.upgrade(4);
+
+        let testing = diff(&before, &after).unwrap();
+
+        let expected = vec! [
+            Change { id: 4, before: 2, after: 1 },
+            Change { id: 5, before: 3, after: 2 }
+        ];
+
+        assert_eq!(testing.depths, expected);
+        assert_eq!(testing.subtrees, vec! [4]);
+    }
+}